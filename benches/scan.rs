@@ -0,0 +1,58 @@
+//! Scanner throughput over multi-megabyte scripts, run with `cargo bench --bench scan`
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rlox::scanner::Scanner;
+
+/// A bit of everything the scanner handles, repeated to fill the input
+const PROGRAM: &str = r#"
+/// Accumulates a running total
+class Counter < Base {
+    init(start) {
+        this.total = start;
+    }
+
+    add(amount) {
+        this.total = this.total + amount * 2 - 0x1F % 3 ~/ 0b101;
+        return this;
+    }
+}
+
+/* block comments /* nest */ too */
+fun greet(név, count) {
+    var message = "Hello, ${név}! You have ${count} new messages";
+    for (var i = 0; i < 1_000; i = i + 1) {
+        if (i >= 12.5e-3 and !(i == 42) or i != 7) print message;
+    }
+    return "tab\tseparated\nlines \u{1F600}";
+}
+
+var counter = Counter(0).add(3.14159);
+print greet("world", counter.total);
+"#;
+
+/// Only string literals, half of them with escapes that force an allocation
+const STRINGS: &str = r#"print "a plain string literal without any escapes in it";
+print "one with \"escapes\" that\tneed\ndecoding";
+"#;
+
+fn repeat(snippet: &str, bytes: usize) -> String {
+    snippet.repeat(bytes / snippet.len() + 1)
+}
+
+fn scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(20);
+    for (name, snippet) in [("program", PROGRAM), ("strings", STRINGS)] {
+        let source = repeat(snippet, 4 * 1024 * 1024);
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| Scanner::new(black_box(&source)).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scan);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::class::LoxInstance;
+use crate::environment::Environment;
+use crate::interpreter::Interpreter;
+use crate::token_type::Token;
+use crate::types::operations::Unwind;
+use crate::types::stmt::Stmt;
+use crate::types::value::Value;
+
+/// Anything that can be invoked with `()` from a script
+pub trait LoxCallable: fmt::Debug + fmt::Display {
+    fn arity(&self) -> usize;
+    /// takes the `Rc` so a class can hand itself to the instances it creates
+    fn call(self: Rc<Self>, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind>;
+}
+
+/// A function declared in a script with `fun`, closing over the scope it was declared in
+pub struct LoxFunction {
+    name: Token,
+    params: Rc<Vec<Token>>,
+    body: Rc<Vec<Stmt>>,
+    closure: Rc<RefCell<Environment>>,
+    /// `init` methods always hand back `this`
+    is_initializer: bool,
+}
+
+impl LoxFunction {
+    pub fn new(
+        name: &Token,
+        params: &Rc<Vec<Token>>,
+        body: &Rc<Vec<Stmt>>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> LoxFunction {
+        LoxFunction {
+            name: name.to_owned(),
+            params: params.clone(),
+            body: body.clone(),
+            closure,
+            is_initializer,
+        }
+    }
+
+    /// A copy of this method whose closure has `this` bound to `instance`
+    pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunction {
+        let mut environment = Environment::with_enclosing(self.closure.clone());
+        environment.define("this", Value::Instance(instance));
+        LoxFunction::new(
+            &self.name,
+            &self.params,
+            &self.body,
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+
+    fn this(&self) -> Value {
+        self.closure.borrow().get_value("this").unwrap_or(Value::Nil)
+    }
+}
+
+impl LoxCallable for LoxFunction {
+    fn arity(&self) -> usize {
+        self.params.len()
+    }
+
+    fn call(self: Rc<Self>, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let mut environment = Environment::with_enclosing(self.closure.clone());
+        for (param, argument) in self.params.iter().zip(arguments) {
+            environment.define(&param.lexeme, argument);
+        }
+
+        match interpreter.execute_block(&self.body, environment) {
+            Ok(_) | Err(Unwind::Return(_)) if self.is_initializer => Ok(self.this()),
+            Ok(_) => Ok(Value::Nil),
+            Err(Unwind::Return(value)) => Ok(value),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+// the closure usually contains the function itself, so it is left out
+impl fmt::Debug for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LoxFunction({})", self.name.lexeme)
+    }
+}
+
+impl fmt::Display for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<fn {}>", self.name.lexeme)
+    }
+}
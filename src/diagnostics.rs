@@ -0,0 +1,406 @@
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::parser::ParseError;
+use crate::resolver::ResolveError;
+use crate::scanner::ScanError;
+use crate::span::Span;
+use crate::token_type::{Token, TokenType};
+use crate::types::operations::RuntimeError;
+
+/// Stable identifiers for every error the toolchain reports, the leading digits
+/// name the phase: `E00xx` scanning, `E01xx` parsing, `E02xx` resolving, `E03xx` runtime
+pub mod codes {
+    pub const UNEXPECTED_CHARACTER: &str = "E0001";
+    pub const UNTERMINATED_STRING: &str = "E0002";
+    pub const UNTERMINATED_COMMENT: &str = "E0003";
+    pub const INVALID_ESCAPE: &str = "E0004";
+    pub const INVALID_CODE_POINT: &str = "E0005";
+    pub const INVALID_NUMBER: &str = "E0006";
+
+    pub const EXPECTED_TOKEN: &str = "E0101";
+    pub const EXPECTED_EXPRESSION: &str = "E0102";
+    pub const INVALID_ASSIGNMENT_TARGET: &str = "E0103";
+    pub const TOO_MANY_ARGUMENTS: &str = "E0104";
+
+    pub const DUPLICATE_DECLARATION: &str = "E0201";
+    pub const READ_IN_OWN_INITIALIZER: &str = "E0202";
+    pub const TOP_LEVEL_RETURN: &str = "E0203";
+    pub const RETURN_FROM_INITIALIZER: &str = "E0204";
+    pub const THIS_OUTSIDE_CLASS: &str = "E0205";
+    pub const INHERIT_FROM_SELF: &str = "E0206";
+    pub const SUPER_OUTSIDE_CLASS: &str = "E0207";
+    pub const SUPER_WITHOUT_SUPERCLASS: &str = "E0208";
+
+    pub const INVALID_OPERAND: &str = "E0301";
+    pub const UNDEFINED_VARIABLE: &str = "E0302";
+    pub const UNDEFINED_PROPERTY: &str = "E0303";
+    pub const NOT_CALLABLE: &str = "E0304";
+    pub const ARITY_MISMATCH: &str = "E0305";
+    pub const NOT_AN_INSTANCE: &str = "E0306";
+    pub const INVALID_SUPERCLASS: &str = "E0307";
+    pub const INTEGER_OVERFLOW: &str = "E0308";
+    pub const DIVISION_BY_ZERO: &str = "E0309";
+    pub const STACK_OVERFLOW: &str = "E0310";
+    /// the interpreter reached a state the earlier phases should have ruled out
+    pub const INTERNAL: &str = "E0399";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A span of source pointed at by a diagnostic, the primary label marks where the
+/// problem is and secondary ones point at related code
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub primary: bool,
+    /// the source `span` points into when it is not the one being run, like a function
+    /// defined on an earlier REPL line
+    pub source: Option<Rc<str>>,
+}
+
+impl Label {
+    pub fn primary(span: Span, message: &str) -> Label {
+        Label {
+            span,
+            message: message.to_owned(),
+            primary: true,
+            source: None,
+        }
+    }
+
+    pub fn secondary(span: Span, message: &str) -> Label {
+        Label {
+            span,
+            message: message.to_owned(),
+            primary: false,
+            source: None,
+        }
+    }
+
+    /// A primary label on `token`, shown against the source it was scanned from
+    pub fn at(token: &Token, message: &str) -> Label {
+        Label {
+            source: Some(Rc::clone(token.lexeme.source())),
+            ..Label::primary(token.span, message)
+        }
+    }
+}
+
+/// Everything a phase wants to tell the user about one problem, independent of how it is shown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.to_owned(),
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn with_label(mut self, label: Label) -> Diagnostic {
+        self.labels.push(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Diagnostic {
+        self.notes.push(note.to_owned());
+        self
+    }
+
+    /// Adds the explanation kept for `self.code`, if there is one
+    fn with_code_note(self) -> Diagnostic {
+        match note_for(self.code) {
+            Some(note) => self.with_note(note),
+            None => self,
+        }
+    }
+
+    fn primary_span(&self) -> Option<Span> {
+        self.labels.iter().find(|label| label.primary).map(|label| label.span)
+    }
+}
+
+fn note_for(code: &str) -> Option<&'static str> {
+    match code {
+        codes::TOP_LEVEL_RETURN => Some("'return' is only allowed inside a function or method"),
+        codes::READ_IN_OWN_INITIALIZER => Some("a local variable is not in scope until its initializer has finished"),
+        codes::THIS_OUTSIDE_CLASS => Some("'this' is only bound inside methods"),
+        codes::SUPER_WITHOUT_SUPERCLASS => Some("declare a superclass with `class Name < Base`"),
+        codes::UNDEFINED_VARIABLE => Some("variables must be declared with 'var' before they are used"),
+        codes::STACK_OVERFLOW => Some("calls can only be nested so deep, check for recursion that never ends"),
+        _ => None,
+    }
+}
+
+impl From<&ScanError> for Diagnostic {
+    fn from(err: &ScanError) -> Self {
+        Diagnostic::error(err.code, &err.message)
+            .with_label(Label::primary(err.span, ""))
+            .with_code_note()
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(err: &ParseError) -> Self {
+        let label = if err.token.token_type == TokenType::EOF {
+            "reached the end of the input"
+        } else {
+            ""
+        };
+        Diagnostic::error(err.code, &err.message)
+            .with_label(Label::at(&err.token, label))
+            .with_code_note()
+    }
+}
+
+impl From<&ResolveError> for Diagnostic {
+    fn from(err: &ResolveError) -> Self {
+        let diagnostic = Diagnostic::error(err.code, &err.message)
+            .with_label(Label::at(&err.token, ""));
+        err.secondary
+            .iter()
+            .cloned()
+            .fold(diagnostic, Diagnostic::with_label)
+            .with_code_note()
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(err: &RuntimeError) -> Self {
+        Diagnostic::error(err.code, &err.message)
+            .with_label(Label::at(&err.token, ""))
+            .with_code_note()
+    }
+}
+
+/// How diagnostics are written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("Unknown error format '{}', expected 'human' or 'json'", s)),
+        }
+    }
+}
+
+/// Writes diagnostics for one source file in the chosen format
+pub struct Emitter {
+    format: ErrorFormat,
+    /// the name shown in locations, a path or something like `<repl>`
+    file: String,
+    color: bool,
+}
+
+impl Emitter {
+    pub fn new(format: ErrorFormat, file: &str) -> Emitter {
+        let color = io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Emitter {
+            format,
+            file: file.to_owned(),
+            color,
+        }
+    }
+
+    pub fn emit(&self, source: &str, diagnostic: &Diagnostic) {
+        let rendered = match self.format {
+            ErrorFormat::Human => self.render_human(source, diagnostic),
+            ErrorFormat::Json => self.render_json(diagnostic),
+        };
+        let _ = writeln!(io::stderr().lock(), "{}", rendered);
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color && !text.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.to_owned()
+        }
+    }
+
+    /// ```text
+    /// error[E0301]: Operands must be two numbers or two strings
+    ///  --> script.lox:1:11
+    ///   |
+    /// 1 | print "a" + 1;
+    ///   |           ^
+    /// ```
+    fn render_human(&self, source: &str, diagnostic: &Diagnostic) -> String {
+        let severity_style = match diagnostic.severity {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+            Severity::Note => "1;36",
+        };
+        let mut out = format!(
+            "{}{}",
+            self.paint(severity_style, &format!("{}[{}]", diagnostic.severity, diagnostic.code)),
+            self.paint("1", &format!(": {}", diagnostic.message)),
+        );
+
+        let mut labels: Vec<&Label> = diagnostic.labels.iter().collect();
+        labels.sort_by_key(|label| label.span.start);
+        let width = labels
+            .iter()
+            .map(|label| label.span.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let blank = " ".repeat(width);
+        let bar = self.paint("1;34", "|");
+
+        if let Some(span) = diagnostic.primary_span() {
+            out += &format!(
+                "\n{}{} {}:{}:{}",
+                blank,
+                self.paint("1;34", "-->"),
+                self.file,
+                span.line,
+                span.column
+            );
+        }
+        if !labels.is_empty() {
+            out += &format!("\n{} {}", blank, bar);
+        }
+        for label in labels {
+            let source = label.source.as_deref().unwrap_or(source);
+            // a span that does not fit the source still has its location shown above
+            let Some((text, padding, width_in_chars)) = underline(source, &label.span) else {
+                continue;
+            };
+            let (marker, style) = if label.primary {
+                ('^', severity_style)
+            } else {
+                ('-', "1;34")
+            };
+            let mut marks = marker.to_string().repeat(width_in_chars);
+            if !label.message.is_empty() {
+                marks = format!("{} {}", marks, label.message);
+            }
+            out += &format!(
+                "\n{} {} {}\n{} {} {}{}",
+                self.paint("1;34", &format!("{:>width$}", label.span.line, width = width)),
+                bar,
+                text,
+                blank,
+                bar,
+                padding,
+                self.paint(style, &marks)
+            );
+        }
+        for note in &diagnostic.notes {
+            out += &format!("\n{} {} {}", blank, self.paint("1;34", "="), self.paint("1", &format!("note: {}", note)));
+        }
+        out
+    }
+
+    /// One JSON object on a single line, so each diagnostic can be read as soon as it is written
+    fn render_json(&self, diagnostic: &Diagnostic) -> String {
+        let labels: Vec<String> = diagnostic
+            .labels
+            .iter()
+            .map(|label| {
+                format!(
+                    "{{\"primary\":{},\"message\":{},\"start\":{},\"end\":{},\"line\":{},\"column\":{}}}",
+                    label.primary,
+                    json_string(&label.message),
+                    label.span.start,
+                    label.span.end,
+                    label.span.line,
+                    label.span.column
+                )
+            })
+            .collect();
+        let notes: Vec<String> = diagnostic.notes.iter().map(|note| json_string(note)).collect();
+        format!(
+            "{{\"severity\":{},\"code\":{},\"message\":{},\"file\":{},\"labels\":[{}],\"notes\":[{}]}}",
+            json_string(&diagnostic.severity.to_string()),
+            json_string(diagnostic.code),
+            json_string(&diagnostic.message),
+            json_string(&self.file),
+            labels.join(","),
+            notes.join(",")
+        )
+    }
+}
+
+/// The source line `span` starts on, the whitespace leading up to the span and how many
+/// grapheme clusters to mark, spans running past the end of the line are cut off there.
+/// `None` when the span cannot be from `source`
+fn underline<'a>(source: &'a str, span: &Span) -> Option<(&'a str, String, usize)> {
+    if !source.is_char_boundary(span.start) || !source.is_char_boundary(span.end) {
+        return None;
+    }
+    let line_start = source[..span.start.min(source.len())]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let line_end = source[line_start..]
+        .find('\n')
+        .map_or(source.len(), |i| line_start + i);
+    let text = source[line_start..line_end].trim_end_matches('\r');
+
+    let start = span.start.clamp(line_start, line_start + text.len());
+    let end = span.end.clamp(start, line_start + text.len());
+
+    // keep tabs so the markers line up with what the terminal shows
+    let padding: String = source[line_start..start]
+        .graphemes(true)
+        .map(|g| if g == "\t" { '\t' } else { ' ' })
+        .collect();
+    Some((text, padding, source[start..end].graphemes(true).count().max(1)))
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
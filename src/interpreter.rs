@@ -0,0 +1,81 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::environment::Environment;
+use crate::token_type::Token;
+use crate::types::operations::{Interpret, RuntimeError, Unwind};
+use crate::types::stmt::Stmt;
+use crate::types::value::Value;
+
+/// How many calls may be in progress at once, deeper recursion is reported rather than
+/// left to overflow the native stack
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+/// Holds the state shared by every node while a program is running
+pub struct Interpreter {
+    pub globals: Rc<RefCell<Environment>>,
+    pub environment: Rc<RefCell<Environment>>,
+    /// calls currently in progress
+    pub call_depth: usize,
+}
+
+impl Default for Interpreter {
+    fn default() -> Interpreter {
+        Interpreter::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        Interpreter {
+            environment: globals.clone(),
+            globals,
+            call_depth: 0,
+        }
+    }
+
+    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<(), RuntimeError> {
+        for statement in statements {
+            match statement.interpret(self) {
+                Ok(_) => {}
+                Err(Unwind::Error(err)) => return Err(err),
+                // a stray top level return just ends the program
+                Err(Unwind::Return(_)) => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a variable from the scope the resolver bound it to, unresolved names are globals
+    pub fn look_up_variable(&self, name: &Token, depth: Option<usize>) -> Result<Value, RuntimeError> {
+        match depth {
+            Some(distance) => Environment::get_at(&self.environment, distance, name),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    pub fn assign_variable(&self, name: &Token, depth: Option<usize>, value: Value) -> Result<(), RuntimeError> {
+        match depth {
+            Some(distance) => Environment::assign_at(&self.environment, distance, name, value),
+            None => self.globals.borrow_mut().assign(name, value),
+        }
+    }
+
+    /// Runs `statements` inside `environment`, restoring the current scope afterwards
+    /// even if one of them fails
+    pub fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        environment: Environment,
+    ) -> Result<Value, Unwind> {
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| statement.interpret(self).map(|_| ()));
+
+        self.environment = previous;
+        result.map(|_| Value::Nil)
+    }
+}
@@ -0,0 +1,16 @@
+// the generated operation stubs take their arguments as `&Box<Expr>` / `&Vec<Stmt>`
+#![allow(clippy::borrowed_box, clippy::ptr_arg)]
+
+pub mod token_type;
+pub mod scanner;
+#[cfg(test)]
+mod test;
+pub mod types;
+pub mod parser;
+pub mod span;
+pub mod callable;
+pub mod class;
+pub mod environment;
+pub mod interpreter;
+pub mod resolver;
+pub mod diagnostics;
@@ -1,95 +1,143 @@
-#![feature(let_chains)]
-
 use std::error::Error;
 
 use std::{process::exit, path::PathBuf};
-use std::{fs, env};
+use std::{fs, env, thread};
+use std::cell::RefCell;
 use std::io::{self, Write};
 
-use scanner::Scanner;
-use token_type::{TokenType, Token, Literal};
-use types::expr::Expr;
-use types::operations::Print;
-use parser::Parser;
+use rlox::diagnostics::{Diagnostic, Emitter, ErrorFormat};
+use rlox::scanner::{ScanError, Scanner};
+use rlox::parser::Parser;
+use rlox::interpreter::Interpreter;
+use rlox::resolver::Resolver;
+use rlox::token_type::TokenType;
 
-mod token_type;
-mod scanner;
-mod test;
-mod types;
-mod parser;
+const USAGE: &str = "Usage: rlox [--error-format=human|json] [script]";
 
+/// The interpreter recurses on the native stack, this leaves room for `MAX_CALL_DEPTH`
+/// calls to deeply nested code before the call limit is reported
+const STACK_SIZE: usize = 256 * 1024 * 1024;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() > 2 {
-        println!("Usage: rlox [script]");
-        exit(64);
-    } else if args.len() == 2 {
-        let file_path = &args[1];
-        println!("In file {}", file_path);
-        let _ = run_file(file_path.into());
-    } else {
-        run_prompt();
+    let cli = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(cli)
+        .expect("failed to start the interpreter thread");
+    if cli.join().is_err() {
+        // the panic has already been printed
+        exit(101);
     }
 }
 
-fn run_file(path: PathBuf) -> Result<(), Box<dyn Error>> {
-    let script = fs::read_to_string(path)?;
-    run(&script);
-    Ok(())
+fn cli() {
+    let mut format = ErrorFormat::Human;
+    let mut scripts = vec![];
+    for arg in env::args().skip(1) {
+        if let Some(value) = arg.strip_prefix("--error-format=") {
+            format = value.parse().unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(64);
+            });
+        } else {
+            scripts.push(arg);
+        }
+    }
+
+    match scripts.as_slice() {
+        [] => run_prompt(format),
+        [file_path] => {
+            if let Err(err) = run_file(file_path.into(), format) {
+                eprintln!("Could not read '{}': {}", file_path, err);
+                exit(66);
+            }
+        }
+        _ => {
+            println!("{}", USAGE);
+            exit(64);
+        }
+    }
+}
+
+fn run_file(path: PathBuf, format: ErrorFormat) -> Result<(), Box<dyn Error>> {
+    let script = fs::read_to_string(&path)?;
+    let emitter = Emitter::new(format, &path.display().to_string());
+    let mut interpreter = Interpreter::new();
+    match run(&script, &mut interpreter, &emitter) {
+        Ok(()) => Ok(()),
+        Err(Failure::Static) => exit(65),
+        Err(Failure::Runtime) => exit(70),
+    }
 }
 
-fn run_prompt() {
+fn run_prompt(format: ErrorFormat) {
     println!("Running Prompt mode");
+    let emitter = Emitter::new(format, "<repl>");
+    let mut interpreter = Interpreter::new();
     loop {
         print!("> ");
         io::stdout().flush().unwrap();
         let mut line = String::new();
 
-        io::stdin().read_line(&mut line).expect("failed to readline");
-        run(&line);
+        if io::stdin().read_line(&mut line).expect("failed to readline") == 0 {
+            // end of input
+            return;
+        }
+        // errors have already been reported, keep the prompt alive
+        let _ = run(&line, &mut interpreter, &emitter);
     }
 }
 
-fn run(source: &str) {
-    // let tokens: Vec<char> = source.chars().collect();
-
-    let mut scanner = Scanner::new(source);
-    let tokens = scanner.scan_tokens();
-    let mut parser = Parser::new(tokens.clone());
-    let expression_result = parser.parse();
-
-    // stop if there wan an error
+/// Which phase a run failed in, used to pick the exit code
+enum Failure {
+    Static,
+    Runtime,
+}
 
-    match expression_result {
-        Ok(_) => {
-            let representation = expression_result.unwrap().print();
-            println!("{}", representation);
-        },
-        Err(err) => println!("Parse Error: {}", err.0),
-        // stop here
+fn run(source: &str, interpreter: &mut Interpreter, emitter: &Emitter) -> Result<(), Failure> {
+    // scan errors are set aside as the parser pulls tokens past them, parsing carries on
+    // so syntax errors are reported in the same run
+    let scan_errors = RefCell::new(vec![]);
+    let tokens = Scanner::new(source).filter_map(|token| match token {
+        Ok(token) => Some(token),
+        Err(err) => {
+            scan_errors.borrow_mut().push(err);
+            None
+        }
+    });
+    let parsed = Parser::new(tokens).parse();
+    let scan_errors = scan_errors.into_inner();
+
+    let ran_off_end = scan_errors.iter().any(ScanError::runs_to_end);
+    let parse_errors = parsed.as_ref().err().into_iter().flatten();
+    // whatever an unclosed string or comment left missing at the end is not worth reporting
+    let parse_errors = parse_errors.filter(|err| !(ran_off_end && err.token.token_type == TokenType::EOF));
+
+    // both kinds go out in source order, however far ahead the parser had looked
+    let mut diagnostics: Vec<(usize, Diagnostic)> = scan_errors
+        .iter()
+        .map(|err| (err.span.start, Diagnostic::from(err)))
+        .chain(parse_errors.map(|err| (err.token.span.start, Diagnostic::from(err))))
+        .collect();
+    diagnostics.sort_by_key(|(start, _)| *start);
+    for (_, diagnostic) in &diagnostics {
+        emitter.emit(source, diagnostic);
     }
 
+    let statements = match parsed {
+        Ok(statements) if scan_errors.is_empty() => statements,
+        _ => return Err(Failure::Static),
+    };
 
-
-    for (i, token) in tokens.iter().enumerate() {
-        println!("{}: {}", i, token);
+    if let Err(errors) = Resolver::new().resolve(&statements) {
+        for err in &errors {
+            emitter.emit(source, &Diagnostic::from(err));
+        }
+        return Err(Failure::Static);
     }
 
-}
-
-fn print_ast() {
-    let expr = Expr::Binary { left:
-        Box::new(Expr::Unary {
-            operator: Token::new(TokenType::MINUS, "-".to_string(), Literal::None(), 1),
-            right: Box::new(Expr::Literal { value: Literal::Number(123.0) })
-        }),
-        operator: Token::new(TokenType::STAR, "*".to_string(), Literal::None(), 1),
-        right: Box::new(Expr::Grouping { expression:
-            Box::new(Expr::Literal { value: Literal::Number(45.67) })
-        }) };
-
-    let representation = expr.print();
-    println!("{}", representation);
+    if let Err(err) = interpreter.interpret(&statements) {
+        emitter.emit(source, &Diagnostic::from(&err));
+        return Err(Failure::Runtime);
+    }
+    Ok(())
 }
@@ -1,39 +1,427 @@
 
 
 use crate::{
+    diagnostics::codes,
     token_type::{Literal, Token, TokenType},
-    types::expr::Expr,
+    types::{expr::Expr, stmt::Stmt},
 };
 
+use std::cell::Cell;
 use std::fmt;
+use std::rc::Rc;
+
+/// Upper bound on parameters and call arguments
+const MAX_ARGUMENTS: usize = 255;
 
 #[derive(Debug)]
-pub struct ParseError(pub String);
+pub struct ParseError {
+    pub code: &'static str,
+    pub token: Box<Token>,
+    pub message: String,
+}
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Parse error: {}", self.0)
+        if self.token.token_type == TokenType::EOF {
+            write!(f, "[line {}] Error at end: {}", self.token.line(), self.message)
+        } else {
+            write!(f, "[line {}] Error at '{}': {}", self.token.line(), self.token.lexeme, self.message)
+        }
     }
 }
 
 impl std::error::Error for ParseError {}
 
-pub struct Parser {
-    tokens: Vec<Token>,
-    current: usize,
+pub struct Parser<I: Iterator<Item = Token>> {
+    tokens: I,
+    /// the next token, pulled from `tokens` the first time it is looked at
+    lookahead: Option<Token>,
+    previous: Option<Token>,
+    errors: Vec<ParseError>,
 }
 
-impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, current: 0 }
+impl<I: Iterator<Item = Token>> Parser<I> {
+    /// `tokens` are consumed as parsing goes, they must end with an EOF token
+    pub fn new(tokens: I) -> Parser<I> {
+        Parser {
+            tokens,
+            lookahead: None,
+            previous: None,
+            errors: vec![],
+        }
+    }
+
+    /// Parses the whole program, recovering after each syntax error so that every
+    /// one of them is reported together
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Vec<ParseError>> {
+        let mut statements = vec![];
+        while !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    // statements
+
+    /// Statement boundaries are where the parser recovers, a failed declaration is
+    /// recorded and skipped
+    fn declaration(&mut self) -> Option<Stmt> {
+        match self.try_declaration() {
+            Ok(statement) => Some(statement),
+            Err(err) => {
+                self.errors.push(err);
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn try_declaration(&mut self) -> Result<Stmt, ParseError> {
+        // the scanner hangs a `///` comment on the first token of what follows it
+        let doc = self.peek().doc.clone();
+        if self._match(&[TokenType::CLASS]).is_some() {
+            return self.class_declaration(doc);
+        }
+        if self._match(&[TokenType::FUN]).is_some() {
+            return self.function("function", doc);
+        }
+        if self._match(&[TokenType::VAR]).is_some() {
+            return self.var_declaration(doc);
+        }
+        self.statement()
+    }
+
+    fn class_declaration(&mut self, doc: Option<Rc<str>>) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        let name = self.consume(TokenType::IDENTIFIER, "Expect class name")?;
+
+        let superclass = if self._match(&[TokenType::LESS]).is_some() {
+            self.consume(TokenType::IDENTIFIER, "Expect superclass name")?;
+            Some(Box::new(Expr::Variable {
+                name: self.previous(),
+                depth: Cell::new(None),
+            }))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::LEFT_BRACE, "Expect '{' before class body")?;
+
+        let mut methods = vec![];
+        while !self.check(&TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            let doc = self.peek().doc.clone();
+            methods.push(self.function("method", doc)?);
+        }
+
+        let right_brace = self.consume(TokenType::RIGHT_BRACE, "Expect '}' after class body")?;
+        Ok(Stmt::Class {
+            keyword,
+            name,
+            superclass,
+            methods,
+            right_brace,
+            doc,
+        })
+    }
+
+    /// `kind` is only used to word error messages
+    fn function(&mut self, kind: &str, doc: Option<Rc<str>>) -> Result<Stmt, ParseError> {
+        let name = self.consume(TokenType::IDENTIFIER, &format!("Expect {} name", kind))?;
+        self.consume(TokenType::LEFT_PAREN, &format!("Expect '(' after {} name", kind))?;
+
+        let mut params = vec![];
+        if !self.check(&TokenType::RIGHT_PAREN) {
+            loop {
+                if params.len() == MAX_ARGUMENTS {
+                    let token = self.peek().to_owned();
+                    let err = self.error(codes::TOO_MANY_ARGUMENTS, token, format!("Can't have more than {} parameters", MAX_ARGUMENTS));
+                    self.errors.push(err);
+                }
+                params.push(self.consume(TokenType::IDENTIFIER, "Expect parameter name")?);
+
+                if self._match(&[TokenType::COMMA]).is_none() {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after parameters")?;
+
+        self.consume(TokenType::LEFT_BRACE, &format!("Expect '{{' before {} body", kind))?;
+        let body = self.block()?;
+
+        Ok(Stmt::Function {
+            name,
+            params: Rc::new(params),
+            body: Rc::new(body),
+            right_brace: self.previous(),
+            doc,
+        })
+    }
+
+    fn var_declaration(&mut self, doc: Option<Rc<str>>) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        let name = self.consume(TokenType::IDENTIFIER, "Expect variable name")?;
+
+        let initializer = if self._match(&[TokenType::EQUAL]).is_some() {
+            Some(Box::new(self.expression()?))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::SEMICOLON, "Expect ';' after variable declaration")?;
+        Ok(Stmt::Var {
+            keyword,
+            name,
+            initializer,
+            doc,
+        })
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        if self._match(&[TokenType::FOR]).is_some() {
+            return self.for_statement();
+        }
+        if self._match(&[TokenType::IF]).is_some() {
+            return self.if_statement();
+        }
+        if self._match(&[TokenType::PRINT]).is_some() {
+            return self.print_statement();
+        }
+        if self._match(&[TokenType::RETURN]).is_some() {
+            return self.return_statement();
+        }
+        if self._match(&[TokenType::WHILE]).is_some() {
+            return self.while_statement();
+        }
+        if self._match(&[TokenType::LEFT_BRACE]).is_some() {
+            let left_brace = self.previous();
+            return Ok(Stmt::Block {
+                left_brace,
+                statements: self.block()?,
+                right_brace: self.previous(),
+            });
+        }
+        self.expression_statement()
+    }
+
+    /// `for` has no node of its own, it is desugared into a `while` loop wrapped in blocks,
+    /// which run from the `for` keyword to the last token of the body in place of braces
+    fn for_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'for'")?;
+
+        let initializer = if self._match(&[TokenType::SEMICOLON]).is_some() {
+            None
+        } else if self._match(&[TokenType::VAR]).is_some() {
+            Some(self.var_declaration(None)?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if !self.check(&TokenType::SEMICOLON) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        let semicolon = self.consume(TokenType::SEMICOLON, "Expect ';' after loop condition")?;
+
+        let increment = if !self.check(&TokenType::RIGHT_PAREN) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after for clauses")?;
+
+        let mut body = self.statement()?;
+        let end = self.previous();
+
+        if let Some(increment) = increment {
+            body = Stmt::Block {
+                left_brace: keyword.clone(),
+                right_brace: end.clone(),
+                statements: vec![
+                    body,
+                    Stmt::Expression {
+                        expression: Box::new(increment),
+                    },
+                ],
+            };
+        }
+
+        // a missing condition is an always true one sitting where it would have been
+        let condition = condition.unwrap_or(Expr::Literal {
+            value: Literal::Boolean(true),
+            token: semicolon,
+        });
+        body = Stmt::While {
+            keyword: keyword.clone(),
+            condition: Box::new(condition),
+            body: Box::new(body),
+        };
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block {
+                left_brace: keyword,
+                statements: vec![initializer, body],
+                right_brace: end,
+            };
+        }
+
+        Ok(body)
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'if'")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after if condition")?;
+
+        let then_branch = self.statement()?;
+        // the else binds to the nearest if
+        let else_branch = if self._match(&[TokenType::ELSE]).is_some() {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Stmt::If {
+            keyword,
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch,
+        })
+    }
+
+    fn print_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after value")?;
+        Ok(Stmt::Print {
+            keyword,
+            expression: Box::new(value),
+        })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        let value = if !self.check(&TokenType::SEMICOLON) {
+            Some(Box::new(self.expression()?))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::SEMICOLON, "Expect ';' after return value")?;
+        Ok(Stmt::Return { keyword, value })
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous();
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'while'")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after condition")?;
+        let body = self.statement()?;
+
+        Ok(Stmt::While {
+            keyword,
+            condition: Box::new(condition),
+            body: Box::new(body),
+        })
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expr = self.expression()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after expression")?;
+        Ok(Stmt::Expression {
+            expression: Box::new(expr),
+        })
     }
 
-    pub fn parse(&mut self) -> Result<Expr, ParseError> {
-        self.expression()
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut statements = vec![];
+
+        while !self.check(&TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+
+        self.consume(TokenType::RIGHT_BRACE, "Expect '}' after block")?;
+        Ok(statements)
     }
 
+    // expressions
+
     fn expression(&mut self) -> Result<Expr, ParseError> {
-        self.equality()
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.or()?;
+
+        if self._match(&[TokenType::EQUAL]).is_some() {
+            let equals = self.previous();
+            let value = self.assignment()?;
+
+            if let Expr::Variable { name, .. } = expr {
+                return Ok(Expr::Assign {
+                    name,
+                    value: Box::new(value),
+                    depth: Cell::new(None),
+                });
+            }
+            if let Expr::Get { object, name } = expr {
+                return Ok(Expr::Set {
+                    object,
+                    name,
+                    value: Box::new(value),
+                });
+            }
+
+            // the parser is not confused, so report and carry on
+            let err = self.error(codes::INVALID_ASSIGNMENT_TARGET, equals, "Invalid assignment target".to_owned());
+            self.errors.push(err);
+        }
+
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+
+        while self._match(&[TokenType::OR]).is_some() {
+            let operator = self.previous();
+            let right = self.and()?;
+            expr = Expr::Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.equality()?;
+
+        while self._match(&[TokenType::AND]).is_some() {
+            let operator = self.previous();
+            let right = self.equality()?;
+            expr = Expr::Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
     }
 
     fn equality(&mut self) -> Result<Expr, ParseError> {
@@ -55,7 +443,15 @@ impl Parser {
     fn comparison(&mut self) -> Result<Expr, ParseError> {
         let mut expr = self.term();
 
-        while self._match(&[TokenType::GREATER]).is_some() {
+        while self
+            ._match(&[
+                TokenType::GREATER,
+                TokenType::GREATER_EQUAL,
+                TokenType::LESS,
+                TokenType::LESS_EQUAL,
+            ])
+            .is_some()
+        {
             let operator = self.previous();
             let right = self.term()?;
             expr = Ok(Expr::Binary {
@@ -83,7 +479,7 @@ impl Parser {
 
     fn factor(&mut self) -> Result<Expr, ParseError> {
         let mut expr = self.unary();
-        while self._match(&[TokenType::SLASH, TokenType::STAR]).is_some() {
+        while self._match(&[TokenType::PERCENT, TokenType::SLASH, TokenType::STAR, TokenType::TILDE_SLASH]).is_some() {
             let operator = self.previous();
             let right = self.unary()?;
             expr = Ok(Expr::Binary {
@@ -105,7 +501,53 @@ impl Parser {
             });
         }
 
-        self.primary()
+        self.call()
+    }
+
+    fn call(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self._match(&[TokenType::LEFT_PAREN]).is_some() {
+                expr = self.finish_call(expr)?;
+            } else if self._match(&[TokenType::DOT]).is_some() {
+                let name = self.consume(TokenType::IDENTIFIER, "Expect property name after '.'")?;
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name,
+                };
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, ParseError> {
+        let mut arguments = vec![];
+        if !self.check(&TokenType::RIGHT_PAREN) {
+            loop {
+                if arguments.len() == MAX_ARGUMENTS {
+                    let token = self.peek().to_owned();
+                    let err = self.error(codes::TOO_MANY_ARGUMENTS, token, format!("Can't have more than {} arguments", MAX_ARGUMENTS));
+                    self.errors.push(err);
+                }
+                arguments.push(self.expression()?);
+
+                if self._match(&[TokenType::COMMA]).is_none() {
+                    break;
+                }
+            }
+        }
+
+        let paren = self.consume(TokenType::RIGHT_PAREN, "Expect ')' after arguments")?;
+
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+        })
     }
 
     fn primary(&mut self) -> Result<Expr, ParseError> {
@@ -114,32 +556,95 @@ impl Parser {
             match typ {
                 TokenType::FALSE => Ok(Expr::Literal {
                     value: Literal::Boolean(false),
+                    token: self.previous(),
                 }),
                 TokenType::NIL => Ok(Expr::Literal {
                     value: Literal::None(),
+                    token: self.previous(),
                 }),
                 TokenType::TRUE => Ok(Expr::Literal {
                     value: Literal::Boolean(true),
+                    token: self.previous(),
                 }),
-                _ => Err(ParseError("Random match".to_owned())),
+                _ => {
+                    let token = self.previous();
+                    Err(self.error(codes::EXPECTED_EXPRESSION, token, "Random match".to_owned()))
+                }
             }
+        } else if self._match(&[TokenType::INTERPOLATION_START]).is_some() {
+            self.interpolation()
         } else if self._match(&[TokenType::NUMBER, TokenType::STRING]).is_some() {
+            let token = self.previous();
             Ok(Expr::Literal {
-                value: self.previous().literal,
+                value: token.literal.clone(),
+                token,
+            })
+        } else if self._match(&[TokenType::SUPER]).is_some() {
+            let keyword = self.previous();
+            self.consume(TokenType::DOT, "Expect '.' after 'super'")?;
+            let method = self.consume(TokenType::IDENTIFIER, "Expect superclass method name")?;
+            Ok(Expr::Super {
+                keyword,
+                method,
+                depth: Cell::new(None),
+            })
+        } else if self._match(&[TokenType::THIS]).is_some() {
+            Ok(Expr::This {
+                keyword: self.previous(),
+                depth: Cell::new(None),
+            })
+        } else if self._match(&[TokenType::IDENTIFIER]).is_some() {
+            Ok(Expr::Variable {
+                name: self.previous(),
+                depth: Cell::new(None),
             })
         } else if self._match(&[TokenType::LEFT_PAREN]).is_some() {
+            let left_paren = self.previous();
             let expr = self.expression()?;
-            self.consume(TokenType::RIGHT_PAREN, "Expect ')' after expression")?;
+            let right_paren = self.consume(TokenType::RIGHT_PAREN, "Expect ')' after expression")?;
             Ok(Expr::Grouping {
+                left_paren,
                 expression: Box::new(expr),
+                right_paren,
             })
         } else {
-            Err(ParseError("Un matched primary".to_owned()))
+            let token = self.peek().to_owned();
+            Err(self.error(codes::EXPECTED_EXPRESSION, token, "Expect expression".to_owned()))
         }
         // TODO: add error handling, this method will return a Result, withc means, all above will
         // be the same
     }
 
+    /// `"a ${b} c ${d} e"` arrives as INTERPOLATION_START holding `a `, the tokens of `b`,
+    /// INTERPOLATION_MIDDLE holding ` c `, the tokens of `d` and INTERPOLATION_END holding ` e`
+    fn interpolation(&mut self) -> Result<Expr, ParseError> {
+        let start = self.previous();
+        let mut parts = vec![];
+        let mut segment = start.clone();
+        loop {
+            if !matches!(&segment.literal, Literal::String(s) if s.is_empty()) {
+                parts.push(Expr::Literal {
+                    value: segment.literal.clone(),
+                    token: segment,
+                });
+            }
+            parts.push(self.expression()?);
+
+            if self._match(&[TokenType::INTERPOLATION_MIDDLE]).is_some() {
+                segment = self.previous();
+                continue;
+            }
+            let end = self.consume(TokenType::INTERPOLATION_END, "Expect '}' after interpolated expression")?;
+            if !matches!(&end.literal, Literal::String(s) if s.is_empty()) {
+                parts.push(Expr::Literal {
+                    value: end.literal.clone(),
+                    token: end.clone(),
+                });
+            }
+            return Ok(Expr::Interpolation { start, parts, end });
+        }
+    }
+
     // helpers
 
     fn _match(&mut self, types: &[TokenType]) -> Option<TokenType> {
@@ -162,12 +667,12 @@ impl Parser {
         if self.is_at_end() {
             return false;
         }
-        return &self.peek().token_type == typ;
+        &self.peek().token_type == typ
     }
 
     fn advance(&mut self) -> Token {
         if !self.is_at_end() {
-            self.current += 1;
+            self.previous = self.lookahead.take();
         }
         self.previous()
     }
@@ -177,11 +682,12 @@ impl Parser {
     }
 
     fn peek(&mut self) -> &Token {
-        self.tokens.get(self.current).unwrap()
+        self.lookahead
+            .get_or_insert_with(|| self.tokens.next().expect("token stream ended without EOF"))
     }
 
     fn previous(&mut self) -> Token {
-        self.tokens.get(self.current - 1).unwrap().to_owned()
+        self.previous.clone().expect("no token has been consumed yet")
     }
 
     fn consume(&mut self, typ: TokenType, message: &str) -> Result<Token, ParseError> {
@@ -189,12 +695,11 @@ impl Parser {
             return Result::Ok(self.advance());
         }
         let token = self.peek().to_owned();
-        Result::Err(self.error(token, message.to_owned()))
+        Result::Err(self.error(codes::EXPECTED_TOKEN, token, message.to_owned()))
     }
 
-    fn error(&mut self, token: Token, message: String) -> ParseError {
-        // Lox.error(token, message)
-        ParseError(message)
+    fn error(&mut self, code: &'static str, token: Token, message: String) -> ParseError {
+        ParseError { code, token: Box::new(token), message }
     }
 
     fn synchronize(&mut self) {
@@ -0,0 +1,174 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::diagnostics::{codes, Label};
+use crate::span::Span;
+use crate::token_type::Token;
+use crate::types::operations::Resolve;
+use crate::types::stmt::Stmt;
+
+#[derive(Debug)]
+pub struct ResolveError {
+    pub code: &'static str,
+    pub token: Box<Token>,
+    pub message: String,
+    /// related code worth pointing at, such as an earlier declaration
+    pub secondary: Option<Label>,
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[line {}] Error at '{}': {}", self.token.line(), self.token.lexeme, self.message)
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// A name declared in a local scope
+struct Binding {
+    /// whether its initializer has finished
+    defined: bool,
+    /// where it was declared, empty for implicit names such as `this`
+    span: Span,
+}
+
+/// Walks the program once before it runs, binding every variable use to the scope it
+/// refers to so the interpreter never has to search for it by name
+pub struct Resolver {
+    /// one map per local scope
+    scopes: Vec<HashMap<String, Binding>>,
+    pub current_function: FunctionType,
+    pub current_class: ClassType,
+    errors: Vec<ResolveError>,
+}
+
+impl Default for Resolver {
+    fn default() -> Resolver {
+        Resolver::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver {
+            scopes: vec![],
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            errors: vec![],
+        }
+    }
+
+    pub fn resolve(mut self, statements: &[Stmt]) -> Result<(), Vec<ResolveError>> {
+        self.resolve_all(statements);
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    pub fn resolve_all(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            statement.resolve(self);
+        }
+    }
+
+    pub fn resolve_function(&mut self, params: &[Token], body: &[Stmt], kind: FunctionType) {
+        let enclosing_function = self.current_function;
+        self.current_function = kind;
+
+        self.begin_scope();
+        for param in params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve_all(body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    /// Records how many scopes away `name` was declared, globals are left unresolved
+    pub fn resolve_local(&mut self, name: &Token, depth: &Cell<Option<usize>>) {
+        for (i, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(name.lexeme.as_str()) {
+                depth.set(Some(i));
+                return;
+            }
+        }
+    }
+
+    pub fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn declare(&mut self, name: &Token) {
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+
+        if let Some(previous) = scope.get(name.lexeme.as_str()) {
+            let previous = Label::secondary(previous.span, "first declared here");
+            self.errors.push(ResolveError {
+                code: codes::DUPLICATE_DECLARATION,
+                token: Box::new(name.to_owned()),
+                message: "Already a variable with this name in this scope".to_owned(),
+                secondary: Some(previous),
+            });
+            return;
+        }
+        scope.insert(name.lexeme.to_string(), Binding { defined: false, span: name.span });
+    }
+
+    pub fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut()
+            && let Some(binding) = scope.get_mut(name.lexeme.as_str())
+        {
+            binding.defined = true;
+        }
+    }
+
+    /// Binds an implicit name such as `this` in the innermost scope
+    pub fn define_keyword(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), Binding { defined: true, span: Span::default() });
+        }
+    }
+
+    /// True while `name` is declared in the innermost scope but its initializer is still running
+    pub fn is_uninitialized(&self, name: &Token) -> bool {
+        self.scopes
+            .last()
+            .and_then(|scope| scope.get(name.lexeme.as_str()))
+            .is_some_and(|binding| !binding.defined)
+    }
+
+    pub fn error(&mut self, code: &'static str, token: &Token, message: &str) {
+        self.errors.push(ResolveError {
+            code,
+            token: Box::new(token.to_owned()),
+            message: message.to_owned(),
+            secondary: None,
+        });
+    }
+}
@@ -1,24 +1,79 @@
-use core::panic;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use unicode_segmentation::GraphemeCursor;
+use unicode_xid::UnicodeXID;
+
+use crate::diagnostics::codes;
+use crate::span::Span;
+use crate::token_type::{Token, Literal, Text, TokenType};
+
+#[derive(Debug)]
+pub struct ScanError {
+    pub code: &'static str,
+    pub span: Span,
+    pub message: String,
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[line {}] Error: {}", self.span.line, self.message)
+    }
+}
 
-use crate::token_type::{Token, Literal, TokenType};
+impl std::error::Error for ScanError {}
+
+impl ScanError {
+    /// Whether the rest of the input was swallowed, an unclosed string or comment, so
+    /// anything the parser finds missing at the end is down to this
+    pub fn runs_to_end(&self) -> bool {
+        self.code == codes::UNTERMINATED_STRING || self.code == codes::UNTERMINATED_COMMENT
+    }
+}
 
+/// Produces tokens lazily, each call to `next` scans only as far as the next token or
+/// error, and the stream always finishes with an EOF token. Tokens point into one shared
+/// copy of the source rather than owning their text
 pub struct Scanner {
-    source: Vec<char>,
-    tokens: Vec<Token>,
+    source: Rc<str>,
+    /// scanned but not yet handed out, one character can yield several errors and a token
+    pending: VecDeque<Result<Token, ScanError>>,
+    /// set once the EOF token has been queued
+    finished: bool,
+    /// lines of the `///` comments seen since the last token
+    doc: Vec<String>,
+    /// the `${` interpolations still open, innermost last, with the braces opened inside
+    /// each so far and where the string holding it began
+    interpolations: Vec<(usize, Span)>,
+    // byte offsets into `source`
     start: usize,
     current: usize,
-    line: i32
+    line: i32,
+    // where the token being scanned begins
+    start_line: i32,
+    start_column: usize,
+    /// column of `current`, counted in grapheme clusters so `é` written as `e` plus a
+    /// combining accent is one column
+    column: usize,
 }
 
 impl Scanner {
     pub fn new(source: &str) -> Scanner {
-        // let b = UnicodeSegmentation::graphemes(&source, true).collect::<Vec<&str>>();
         Scanner {
-            source: source.chars().collect(),
-            tokens: vec![],
+            source: Rc::from(source),
+            pending: VecDeque::new(),
+            finished: false,
+            doc: vec![],
+            interpolations: vec![],
             start: 0,
             current: 0,
-            line: 1
+            line: 1,
+            start_line: 1,
+            start_column: 1,
+            column: 1,
         }
     }
 
@@ -26,74 +81,156 @@ impl Scanner {
         self.current >= self.source.len()
     }
 
-    pub fn scan_tokens(&mut self) -> Vec<Token> {
-        loop {
-            if self.is_at_end() {
-                break;
-            }
-
-            self.start = self.current;
-            self.scan_token();
+    /// Queues the EOF token, reporting any string still open at the end of the input
+    fn finish(&mut self) {
+        self.start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.column;
+        while let Some((_, start)) = self.interpolations.pop() {
+            self.error_at(codes::UNTERMINATED_STRING, self.since(start), "Unterminated string");
+            // closes the interpolation for the parser, like the string's missing `}.."`
+            self.add_value_token(TokenType::INTERPOLATION_END, Literal::String(self.lexeme()));
         }
-
-        self.tokens.push(Token::new(TokenType::EOF, "".to_string(), Literal::None(), self.line));
-        self.tokens.clone()
+        self.add_token(TokenType::EOF);
+        self.finished = true;
     }
 
     fn scan_token(&mut self) {
-        let n = self.line;
         let chr = self.advance();
         match chr {
             '(' => self.add_token(TokenType::LEFT_PAREN),
             ')' => self.add_token(TokenType::RIGHT_PAREN),
-            '{' => self.add_token(TokenType::LEFT_BRACE),
-            '}' => self.add_token(TokenType::RIGHT_BRACE),
+            '{' => {
+                if let Some((braces, _)) = self.interpolations.last_mut() {
+                    *braces += 1;
+                }
+                self.add_token(TokenType::LEFT_BRACE)
+            }
+            '}' => match self.interpolations.last_mut() {
+                // closes a `${`, what follows is more of the string
+                Some((0, start)) => {
+                    let start = *start;
+                    self.interpolations.pop();
+                    self.string(start, true);
+                }
+                Some((braces, _)) => {
+                    *braces -= 1;
+                    self.add_token(TokenType::RIGHT_BRACE)
+                }
+                None => self.add_token(TokenType::RIGHT_BRACE),
+            },
             ',' => self.add_token(TokenType::COMMA),
             '.' => self.add_token(TokenType::DOT),
             '-' => self.add_token(TokenType::MINUS),
+            '%' => self.add_token(TokenType::PERCENT),
             '+' => self.add_token(TokenType::PLUS),
             ';' => self.add_token(TokenType::SEMICOLON),
             '*' => self.add_token(TokenType::STAR),
 
-            '!' => self.add_match_or(&'=', TokenType::BANG_EQUAL, TokenType::BANG),
-            '=' => self.add_match_or(&'=', TokenType::EQUAL_EQUAL, TokenType::EQUAL ),
-            '<' => self.add_match_or(&'=', TokenType::LESS_EQUAL, TokenType::LESS),
-            '>' => self.add_match_or(&'=', TokenType::GREATER_EQUAL, TokenType::GREATER),
+            '!' => self.add_match_or('=', TokenType::BANG_EQUAL, TokenType::BANG),
+            '=' => self.add_match_or('=', TokenType::EQUAL_EQUAL, TokenType::EQUAL ),
+            '<' => self.add_match_or('=', TokenType::LESS_EQUAL, TokenType::LESS),
+            '>' => self.add_match_or('=', TokenType::GREATER_EQUAL, TokenType::GREATER),
+
+            '~' if self.peek().is_some_and(|c| c == '/') => {
+                self.advance();
+                self.add_token(TokenType::TILDE_SLASH);
+            }
 
             '/' => {
-                if self.peek().is_some_and(|c| c == &'/') {
+                if self.peek().is_some_and(|c| c == '/') {
                     let _ = self.advance();
-                    while !self.is_at_end() && self.peek().is_some_and(|c|c != &'\n') {  // TODO: is_at_end check might be redundant here
-                        let _ = self.advance();
-                    }
+                    self.line_comment();
+                } else if self.peek().is_some_and(|c| c == '*') {
+                    let _ = self.advance();
+                    self.block_comment();
                 } else {
                     self.add_token(TokenType::SLASH);
                 }
 
             },
 
-            ' ' | '\r' | '\t'  => {},
-            '\n' => self.line += 1,
+            // line bookkeeping happens in `advance`
+            ' ' | '\r' | '\t' | '\n' => {},
 
-            '"' => self.string(),
+            '"' => self.string(self.span(), false),
 
 
             x => {
                 if x.is_ascii_digit() {
                     self.number()
-                } else if x.is_alphabetic() || x == &'_' {
+                } else if x.is_xid_start() || x == '_' {
                     self.identifier();
                 } else {
-                    panic!("{} Unexpected character: {}", n, x)
+                    self.error(codes::UNEXPECTED_CHARACTER, &format!("Unexpected character '{}'", x));
                 }
             }
         }
     }
 
-    fn advance(&mut self) -> &char {
-        let char = self.source.get(self.current);
-        self.current += 1;
-        char.unwrap()
+    fn error(&mut self, code: &'static str, message: &str) {
+        self.error_at(code, self.span(), message);
+    }
+
+    /// Reports a problem with part of the token rather than all of it
+    fn error_at(&mut self, code: &'static str, span: Span, message: &str) {
+        self.pending.push_back(Err(ScanError {
+            code,
+            span,
+            message: message.to_owned(),
+        }));
+    }
+
+    /// The span of the token scanned so far
+    fn span(&self) -> Span {
+        Span {
+            start: self.start,
+            end: self.current,
+            line: self.start_line,
+            column: self.start_column,
+        }
+    }
+
+    /// An empty span at the next character to be scanned
+    fn here(&self) -> Span {
+        Span {
+            start: self.current,
+            end: self.current,
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    /// From `start` up to the next character to be scanned
+    fn since(&self, start: Span) -> Span {
+        Span {
+            end: self.current,
+            ..start
+        }
+    }
+
+    fn advance(&mut self) -> char {
+        let char = self.peek().unwrap();
+        if char == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if self.starts_cluster(self.current) {
+            self.column += 1;
+        }
+        self.current += char.len_utf8();
+        char
+    }
+
+    /// Whether the char at byte `index` begins a grapheme cluster, only checked properly
+    /// when a non-ASCII char is involved as ASCII chars are their own clusters
+    fn starts_cluster(&self, index: usize) -> bool {
+        let bytes = self.source.as_bytes();
+        if bytes[index].is_ascii() && (index == 0 || bytes[index - 1].is_ascii()) {
+            return true;
+        }
+        GraphemeCursor::new(index, self.source.len(), true)
+            .is_boundary(&self.source, 0)
+            .unwrap_or(true)
     }
 
     fn add_token(&mut self, tt: TokenType) {
@@ -101,24 +238,28 @@ impl Scanner {
     }
 
     fn add_value_token(&mut self, tt: TokenType, literal: Literal) {
-        let chars = self.source.get(self.start..self.current).unwrap();
-        let text = String::from_iter(chars);
-        self.tokens.push(Token::new(tt, text, literal, self.line))
+        self.push_token(tt, self.lexeme(), literal);
     }
 
-    fn matches(&mut self, expected: &char) -> bool {
-        if self.is_at_end() {
-            return false;
-        }
-        if self.source.get(self.current).is_some_and(|x| x == expected) {
-            return false;
+    fn push_token(&mut self, tt: TokenType, lexeme: Text, literal: Literal) {
+        let mut token = Token::new(tt, lexeme, literal, self.span());
+        if !self.doc.is_empty() {
+            token.doc = Some(self.doc.join("\n").into());
+            self.doc.clear();
         }
+        self.pending.push_back(Ok(token))
+    }
+
+    /// The token scanned so far
+    fn lexeme(&self) -> Text {
+        self.slice(self.start..self.current)
+    }
 
-        self.current += 1;
-        true
+    fn slice(&self, range: std::ops::Range<usize>) -> Text {
+        Text::new(Rc::clone(&self.source), range)
     }
 
-    fn add_match_or(&mut self, chr: &char, matches: TokenType, no_match: TokenType) {
+    fn add_match_or(&mut self, chr: char, matches: TokenType, no_match: TokenType) {
         if self.peek().is_some_and(|c| c == chr) {
             let _ = self.advance();
             self.add_token(matches);
@@ -128,67 +269,282 @@ impl Scanner {
         }
     }
 
-    fn peek(&self) -> Option<&char> {
-        if self.is_at_end() {
-            return None;
+    fn peek(&self) -> Option<char> {
+        self.source[self.current..].chars().next()
+    }
+
+    /// Skips the rest of a `//` comment, `///` ones (but not `////`) are kept for the next token
+    fn line_comment(&mut self) {
+        let is_doc = self.peek().is_some_and(|c| c == '/') && self.peek_next().is_none_or(|c| c != '/');
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.advance();
+        }
+
+        if is_doc {
+            let text = &self.source[(self.start + 3)..self.current];
+            let text = text.trim_end_matches('\r');
+            self.doc.push(text.strip_prefix(' ').unwrap_or(text).to_owned());
         }
-        self.source.get(self.current)
     }
 
-    fn string(&mut self) {
-        while self.peek().is_some_and(|c| c != &'"') && !self.is_at_end() { // is_at_end check might be redundant
-            if self.peek().is_some_and(|c| c == &'\n') {
-                self.line += 1;
+    /// Skips a `/* ... */` comment, which may contain nested ones
+    fn block_comment(&mut self) {
+        let mut depth = 1;
+        while depth > 0 {
+            match (self.peek(), self.peek_next()) {
+                (None, _) => {
+                    self.error(codes::UNTERMINATED_COMMENT, "Unterminated block comment");
+                    return;
+                }
+                (Some('/'), Some('*')) => {
+                    self.advance();
+                    self.advance();
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
+                    self.advance();
+                    self.advance();
+                    depth -= 1;
+                }
+                _ => {
+                    self.advance();
+                }
             }
-            self.advance();
         }
+    }
+
+    /// Scans up to the closing quote or the next `${`, `start` is the string's opening
+    /// quote so an unterminated string is reported from there, `resumed` when this
+    /// carries on after the `}` of an interpolation
+    fn string(&mut self, start: Span, resumed: bool) {
+        let content_start = self.current;
+        // only built once an escape makes the value differ from the source text
+        let mut escaped: Option<String> = None;
+        let terminated = loop {
+            match self.peek() {
+                None => {
+                    self.error_at(codes::UNTERMINATED_STRING, self.since(start), "Unterminated string");
+                    break false;
+                }
+                Some('"') => break true,
+                Some('$') if self.peek_next().is_some_and(|c| c == '{') => {
+                    let value = self.string_value(content_start, escaped);
+                    self.advance();
+                    self.advance();
+                    self.interpolations.push((0, start));
+                    let tt = if resumed {
+                        TokenType::INTERPOLATION_MIDDLE
+                    } else {
+                        TokenType::INTERPOLATION_START
+                    };
+                    self.add_value_token(tt, Literal::String(value));
+                    return;
+                }
+                Some('\\') => {
+                    let start = self.here();
+                    let mut value = escaped
+                        .take()
+                        .unwrap_or_else(|| self.source[content_start..self.current].to_owned());
+                    self.advance();
+                    if let Some(c) = self.escape(start) {
+                        value.push(c);
+                    }
+                    escaped = Some(value);
+                }
+                Some(_) => {
+                    let c = self.advance();
+                    if let Some(value) = &mut escaped {
+                        value.push(c);
+                    }
+                }
+            }
+        };
+
+        // an unterminated string still becomes a token so the parser does not report it again
+        let value = self.string_value(content_start, escaped);
+        if terminated {
+            self.advance(); // consume the ending "
+        }
+        let tt = if resumed {
+            TokenType::INTERPOLATION_END
+        } else {
+            TokenType::STRING
+        };
+        self.add_value_token(tt, Literal::String(value));
+    }
 
-        if self.is_at_end() {
-            panic!("Unterminated string literal");
+    /// The string scanned from `content_start` so far, a slice of the source unless it
+    /// had escapes
+    fn string_value(&self, content_start: usize, escaped: Option<String>) -> Text {
+        match escaped {
+            Some(value) => self.slice(content_start..self.current).rewritten(value),
+            None => self.slice(content_start..self.current),
         }
+    }
 
-        self.advance(); // consume the ending "
+    /// Decodes the escape following a `\` that starts at `start`, bad escapes are
+    /// reported and dropped from the string
+    fn escape(&mut self, start: Span) -> Option<char> {
+        // at the end of input the caller reports the unterminated string
+        let c = self.peek()?;
+        self.advance();
+
+        match c {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            '$' => Some('$'),
+            'u' => self.unicode_escape(start),
+            c => {
+                let message = format!("Invalid escape sequence '\\{}'", c);
+                self.error_at(codes::INVALID_ESCAPE, self.since(start), &message);
+                None
+            }
+        }
+    }
 
-        let str = String::from_iter(self.source.get((self.start + 1)..(self.current - 1)).unwrap());
-        self.add_value_token(TokenType::STRING, Literal::String(str));
+    /// The `{XXXX}` part of a `\u{XXXX}` escape, one to six hex digits naming a code point
+    fn unicode_escape(&mut self, start: Span) -> Option<char> {
+        let malformed = "Malformed unicode escape, expected 1 to 6 hex digits between '\\u{' and '}'";
+        if self.peek().is_none_or(|c| c != '{') {
+            self.error_at(codes::INVALID_ESCAPE, self.since(start), malformed);
+            return None;
+        }
+        self.advance();
 
+        let mut digits = String::new();
+        while let Some(c) = self.peek() && c.is_ascii_hexdigit() {
+            digits.push(c);
+            self.advance();
+        }
+        if self.peek().is_none_or(|c| c != '}') || digits.is_empty() || digits.len() > 6 {
+            self.error_at(codes::INVALID_ESCAPE, self.since(start), malformed);
+            return None;
+        }
+        self.advance();
+
+        let code_point = u32::from_str_radix(&digits, 16).unwrap_or(u32::MAX);
+        let decoded = char::from_u32(code_point);
+        if decoded.is_none() {
+            let message = format!("Invalid code point U+{} in unicode escape", digits.to_uppercase());
+            self.error_at(codes::INVALID_CODE_POINT, self.since(start), &message);
+        }
+        decoded
     }
 
+    /// Decimal (`12`, `12.5`, `1e-9`), hex (`0xFF`) or binary (`0b1010`) literals, any of
+    /// them with `_` between digits, only decimals with a fraction or exponent are floats
     fn number(&mut self) {
-        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+        let prefix = self.peek();
+        let radix = match (self.source.as_bytes()[self.start], prefix) {
+            (b'0', Some('x' | 'X')) => 16,
+            (b'0', Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+
+        let mut is_float = false;
+        if radix == 10 {
+            self.digits(10);
+            if self.peek().is_some_and(|c| c == '.') && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+                // consume the .
+                self.advance();
+                self.digits(10);
+                is_float = true;
+            }
+            if self.at_exponent() {
+                self.advance();
+                if self.peek().is_some_and(|c| c == '+' || c == '-') {
+                    self.advance();
+                }
+                self.digits(10);
+                is_float = true;
+            }
+        } else {
             self.advance();
+            self.digits(radix);
         }
 
-        if self.peek().is_some_and(|c| c == &'.') && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
-            // comsume the .
+        // letters, digits or a stray `_` running straight on make the whole literal malformed
+        let mut malformed = radix != 10 && self.current - self.start == 2;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
             self.advance();
+            malformed = true;
+        }
+        let text = self.lexeme();
+        let literal = if malformed {
+            self.error(codes::INVALID_NUMBER, &format!("Invalid number literal '{}'", text));
+            None
+        } else {
+            let digits = if text.contains('_') {
+                Cow::Owned(text.replace('_', ""))
+            } else {
+                Cow::Borrowed(&*text)
+            };
+            let parsed = if is_float {
+                digits.parse::<f64>().ok().filter(|n| n.is_finite()).map(Literal::Float)
+            } else if radix == 10 {
+                digits.parse::<i64>().ok().map(Literal::Int)
+            } else {
+                i64::from_str_radix(&digits[2..], radix).ok().map(Literal::Int)
+            };
+            if parsed.is_none() {
+                self.error(codes::INVALID_NUMBER, &format!("Number literal '{}' is out of range", text));
+            }
+            parsed
+        };
+        // a bad literal still becomes a token so the parser does not report it again
+        self.add_value_token(TokenType::NUMBER, literal.unwrap_or(Literal::Int(0)));
+    }
 
-            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
-                self.advance();
+    /// Consumes digits in `radix`, an `_` is only taken when it sits between two digits
+    fn digits(&mut self, radix: u32) {
+        // only ever called after an ASCII digit, `.`, exponent or radix prefix
+        let mut seen_digit = char::from(self.source.as_bytes()[self.current - 1]).is_digit(radix);
+        while let Some(c) = self.peek() {
+            let separator = c == '_' && seen_digit && self.peek_next().is_some_and(|c| c.is_digit(radix));
+            if !(c.is_digit(radix) || separator) {
+                break;
             }
+            seen_digit = true;
+            self.advance();
         }
-
-        let str = String::from_iter(self.source.get((self.start)..(self.current)).unwrap());
-        let num: f32 = str.parse().unwrap();
-        self.add_value_token(TokenType::NUMBER, Literal::Number(num));
     }
 
-    fn peek_next(&self) -> Option<&char> {
-        if self.current + 1 >= self.source.len() {
-            return None;
+    /// An `e` followed by digits, optionally signed
+    fn at_exponent(&self) -> bool {
+        if !self.peek().is_some_and(|c| c == 'e' || c == 'E') {
+            return false;
+        }
+        match self.peek_next() {
+            Some('+' | '-') => self.source[self.current..].chars().nth(2).is_some_and(|c| c.is_ascii_digit()),
+            next => next.is_some_and(|c| c.is_ascii_digit()),
         }
-        return self.source.get(self.current + 1)
     }
 
+    fn peek_next(&self) -> Option<char> {
+        self.source[self.current..].chars().nth(1)
+    }
+
+    /// Identifiers follow Unicode's XID rules (plus a leading `_`) and are NFC normalised,
+    /// so names that only differ in how an accent is encoded are the same name
     fn identifier(&mut self) {
-        while let Some(c) = self.peek() && (c.is_alphabetic() || c == &'_') {
+        while let Some(c) = self.peek() && c.is_xid_continue() {
             self.advance();
         }
-        let str = String::from_iter(self.source.get((self.start)..(self.current)).unwrap());
-        let t_type_opt = Scanner::keyword(&str);
-        match t_type_opt {
+        // most names are already NFC and stay a slice of the source
+        let name = self.lexeme();
+        let name = if is_nfc(&name) {
+            name
+        } else {
+            let normalized = name.nfc().collect();
+            name.rewritten(normalized)
+        };
+        match Scanner::keyword(&name) {
             Some(t_type) => self.add_token(t_type),
-            None => self.add_token(TokenType::IDENTIFIER)
+            None => self.push_token(TokenType::IDENTIFIER, name, Literal::None()),
         }
     }
 
@@ -215,4 +571,21 @@ impl Scanner {
     }
 }
 
+impl Iterator for Scanner {
+    type Item = Result<Token, ScanError>;
 
+    /// Scanning carries on past bad characters, so every one of them is reported
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.finished {
+            if self.is_at_end() {
+                self.finish();
+            } else {
+                self.start = self.current;
+                self.start_line = self.line;
+                self.start_column = self.column;
+                self.scan_token();
+            }
+        }
+        self.pending.pop_front()
+    }
+}
@@ -0,0 +1,26 @@
+/// Where a token or node sits in the source, `start..end` are byte offsets and
+/// `line`/`column` (both 1-based, columns in grapheme clusters) are where it begins
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Ord, PartialOrd, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: i32,
+    pub column: usize,
+}
+
+impl Span {
+    /// The smallest span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Span {
+        let (first, last) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        Span {
+            start: first.start,
+            end: first.end.max(last.end),
+            line: first.line,
+            column: first.column,
+        }
+    }
+}
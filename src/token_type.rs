@@ -1,19 +1,28 @@
 use std::{fmt::Display as FmtDisplay};
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::rc::Rc;
 
+use crate::span::Span;
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Ord, PartialOrd, Hash)]
 pub enum TokenType {
     // Single-character tokens.
     LEFT_PAREN, RIGHT_PAREN, LEFT_BRACE, RIGHT_BRACE,
-    COMMA, DOT, MINUS, PLUS, SEMICOLON, SLASH, STAR,
+    COMMA, DOT, MINUS, PERCENT, PLUS, SEMICOLON, SLASH, STAR,
 
     // One or two character tokens.
     BANG, BANG_EQUAL,
     EQUAL, EQUAL_EQUAL,
     GREATER, GREATER_EQUAL,
     LESS, LESS_EQUAL,
+    TILDE_SLASH,
 
     // Literals.
     IDENTIFIER, STRING, NUMBER,
+    // the pieces of a string around its `${...}`s: `"..${`, `}..${` and `}.."`
+    INTERPOLATION_START, INTERPOLATION_MIDDLE, INTERPOLATION_END,
 
     // Keywords.
     AND, CLASS, ELSE, FALSE, FUN, FOR, IF, NIL, OR,
@@ -29,10 +38,105 @@ impl FmtDisplay for TokenType {
 }
 
 
+/// A piece of the source a token was scanned from, `start..end` is its byte range in the
+/// shared `source` so cloning it never copies the text. Text the scanner had to rewrite,
+/// like a string with escapes, keeps its new spelling in `rewritten`
+#[derive(Clone)]
+pub struct Text {
+    source: Rc<str>,
+    start: usize,
+    end: usize,
+    rewritten: Option<Rc<str>>,
+}
+
+impl Text {
+    pub fn new(source: Rc<str>, range: Range<usize>) -> Text {
+        Text {
+            source,
+            start: range.start,
+            end: range.end,
+            rewritten: None,
+        }
+    }
+
+    /// The same stretch of source, spelled as `text` instead
+    pub fn rewritten(self, text: String) -> Text {
+        Text {
+            rewritten: Some(Rc::from(text)),
+            ..self
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.rewritten {
+            Some(text) => text,
+            None => &self.source[self.start..self.end],
+        }
+    }
+
+    /// All of the source this was scanned from, for showing it in context
+    pub fn source(&self) -> &Rc<str> {
+        &self.source
+    }
+}
+
+impl Deref for Text {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for Text {
+    fn from(text: String) -> Text {
+        let end = text.len();
+        Text::new(Rc::from(text), 0..end)
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Text {
+        Text::new(Rc::from(text), 0..text.len())
+    }
+}
+
+impl PartialEq for Text {
+    fn eq(&self, other: &Text) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<&str> for Text {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for Text {
+    fn partial_cmp(&self, other: &Text) -> Option<std::cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl fmt::Debug for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl FmtDisplay for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
 pub enum Literal {
-    String(String),
-    Number(f32),
+    /// only owns its text when escapes made it differ from the source
+    String(Text),
+    Int(i64),
+    Float(f64),
     Boolean(bool),
     None()
 }
@@ -46,24 +150,32 @@ impl FmtDisplay for Literal {
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
 pub struct Token {
     pub token_type: TokenType,
-    pub lexeme: String,
+    /// the token's text in the source, identifiers are NFC normalised
+    pub lexeme: Text,
     pub literal: Literal,
-    pub line: i32
+    pub span: Span,
+    /// leading `///` comment trivia, the text of each line joined with newlines
+    pub doc: Option<Rc<str>>,
 }
 
 impl Token {
-    pub fn new(tt: TokenType, lexeme: String, literal: Literal, line: i32) -> Token {
+    pub fn new(tt: TokenType, lexeme: Text, literal: Literal, span: Span) -> Token {
         Token {
             token_type: tt,
             lexeme,
             literal,
-            line
+            span,
+            doc: None,
         }
     }
+
+    pub fn line(&self) -> i32 {
+        self.span.line
+    }
 }
 
 impl FmtDisplay for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}: ({}) '{}' - {}]", self.line, self.token_type, self.lexeme, self.literal)
+        write!(f, "[{}:{}: ({}) '{}' - {}]", self.span.line, self.span.column, self.token_type, self.lexeme, self.literal)
     }
 }
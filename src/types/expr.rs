@@ -1,11 +1,20 @@
+use std::cell::Cell;
+
 use crate::token_type::{Token,Literal};
 
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
 pub enum Expr {
-    Assign{name: Token, value: Box<Expr>},
+    Assign{name: Token, value: Box<Expr>, depth: Cell<Option<usize>>},
     Binary{left: Box<Expr>, operator: Token, right: Box<Expr>},
-    Grouping{expression: Box<Expr>},
-    Literal{value: Literal},
+    Call{callee: Box<Expr>, paren: Token, arguments: Vec<Expr>},
+    Get{object: Box<Expr>, name: Token},
+    Grouping{left_paren: Token, expression: Box<Expr>, right_paren: Token},
+    Interpolation{start: Token, parts: Vec<Expr>, end: Token},
+    Literal{value: Literal, token: Token},
+    Logical{left: Box<Expr>, operator: Token, right: Box<Expr>},
+    Set{object: Box<Expr>, name: Token, value: Box<Expr>},
+    Super{keyword: Token, method: Token, depth: Cell<Option<usize>>},
+    This{keyword: Token, depth: Cell<Option<usize>>},
     Unary{operator: Token, right: Box<Expr>},
-    Variable{name: Token},
+    Variable{name: Token, depth: Cell<Option<usize>>},
 }
@@ -1,5 +1,6 @@
 pub mod operations;
 pub mod expr;
 pub mod stmt;
+pub mod value;
 
 use crate::token_type::{Literal};
@@ -1,26 +1,47 @@
-use crate::token_type::{Literal};
 use crate::types::expr::Expr;
 use crate::types::stmt::Stmt;
+use crate::types::value::Value;
+use crate::interpreter::Interpreter;
+use crate::resolver::Resolver;
+use crate::span::Span;
+pub use interpret::{RuntimeError, Unwind};
 mod print;
 mod interpret;
+mod resolve;
+mod spanned;
 pub trait Print {
     fn print(&self) -> String;
 }
 
 pub trait Interpret {
-    fn interpret(&self) -> Literal;
+    fn interpret(&self, interpreter: &mut Interpreter) -> Result<Value, Unwind>;
+}
+
+pub trait Resolve {
+    fn resolve(&self, resolver: &mut Resolver);
+}
+
+pub trait Spanned {
+    fn spanned(&self) -> Option<Span>;
 }
 
 
 impl Print for Expr {
     fn print(&self) -> String {
         match self {
-            Expr::Assign { name, value } => print::assign(name, value),
+            Expr::Assign { name, value, depth } => print::assign(name, value, depth),
             Expr::Binary { left, operator, right } => print::binary(left, operator, right),
-            Expr::Grouping { expression } => print::grouping(expression),
-            Expr::Literal { value } => print::literal(value),
+            Expr::Call { callee, paren, arguments } => print::call(callee, paren, arguments),
+            Expr::Get { object, name } => print::get(object, name),
+            Expr::Grouping { left_paren, expression, right_paren } => print::grouping(left_paren, expression, right_paren),
+            Expr::Interpolation { start, parts, end } => print::interpolation(start, parts, end),
+            Expr::Literal { value, token } => print::literal(value, token),
+            Expr::Logical { left, operator, right } => print::logical(left, operator, right),
+            Expr::Set { object, name, value } => print::set(object, name, value),
+            Expr::Super { keyword, method, depth } => print::super_(keyword, method, depth),
+            Expr::This { keyword, depth } => print::this(keyword, depth),
             Expr::Unary { operator, right } => print::unary(operator, right),
-            Expr::Variable { name } => print::variable(name),
+            Expr::Variable { name, depth } => print::variable(name, depth),
         }
     }
 }
@@ -28,34 +49,123 @@ impl Print for Expr {
 impl Print for Stmt {
     fn print(&self) -> String {
         match self {
-            Stmt::Block { statements } => print::block(statements),
+            Stmt::Block { left_brace, statements, right_brace } => print::block(left_brace, statements, right_brace),
+            Stmt::Class { keyword, name, superclass, methods, right_brace, doc } => print::class(keyword, name, superclass, methods, right_brace, doc),
             Stmt::Expression { expression } => print::expression(expression),
-            Stmt::Print { expression } => print::print(expression),
-            Stmt::Var { name, initializer } => print::var(name, initializer),
+            Stmt::Function { name, params, body, right_brace, doc } => print::function(name, params, body, right_brace, doc),
+            Stmt::If { keyword, condition, then_branch, else_branch } => print::r#if(keyword, condition, then_branch, else_branch),
+            Stmt::Print { keyword, expression } => print::print(keyword, expression),
+            Stmt::Return { keyword, value } => print::r#return(keyword, value),
+            Stmt::Var { keyword, name, initializer, doc } => print::var(keyword, name, initializer, doc),
+            Stmt::While { keyword, condition, body } => print::r#while(keyword, condition, body),
         }
     }
 }
 
 impl Interpret for Expr {
-    fn interpret(&self) -> Literal {
+    fn interpret(&self, interpreter: &mut Interpreter) -> Result<Value, Unwind> {
         match self {
-            Expr::Assign { name, value } => interpret::assign(name, value),
-            Expr::Binary { left, operator, right } => interpret::binary(left, operator, right),
-            Expr::Grouping { expression } => interpret::grouping(expression),
-            Expr::Literal { value } => interpret::literal(value),
-            Expr::Unary { operator, right } => interpret::unary(operator, right),
-            Expr::Variable { name } => interpret::variable(name),
+            Expr::Assign { name, value, depth } => interpret::assign(interpreter, name, value, depth),
+            Expr::Binary { left, operator, right } => interpret::binary(interpreter, left, operator, right),
+            Expr::Call { callee, paren, arguments } => interpret::call(interpreter, callee, paren, arguments),
+            Expr::Get { object, name } => interpret::get(interpreter, object, name),
+            Expr::Grouping { left_paren, expression, right_paren } => interpret::grouping(interpreter, left_paren, expression, right_paren),
+            Expr::Interpolation { start, parts, end } => interpret::interpolation(interpreter, start, parts, end),
+            Expr::Literal { value, token } => interpret::literal(interpreter, value, token),
+            Expr::Logical { left, operator, right } => interpret::logical(interpreter, left, operator, right),
+            Expr::Set { object, name, value } => interpret::set(interpreter, object, name, value),
+            Expr::Super { keyword, method, depth } => interpret::super_(interpreter, keyword, method, depth),
+            Expr::This { keyword, depth } => interpret::this(interpreter, keyword, depth),
+            Expr::Unary { operator, right } => interpret::unary(interpreter, operator, right),
+            Expr::Variable { name, depth } => interpret::variable(interpreter, name, depth),
         }
     }
 }
 
 impl Interpret for Stmt {
-    fn interpret(&self) -> Literal {
+    fn interpret(&self, interpreter: &mut Interpreter) -> Result<Value, Unwind> {
+        match self {
+            Stmt::Block { left_brace, statements, right_brace } => interpret::block(interpreter, left_brace, statements, right_brace),
+            Stmt::Class { keyword, name, superclass, methods, right_brace, doc } => interpret::class(interpreter, keyword, name, superclass, methods, right_brace, doc),
+            Stmt::Expression { expression } => interpret::expression(interpreter, expression),
+            Stmt::Function { name, params, body, right_brace, doc } => interpret::function(interpreter, name, params, body, right_brace, doc),
+            Stmt::If { keyword, condition, then_branch, else_branch } => interpret::r#if(interpreter, keyword, condition, then_branch, else_branch),
+            Stmt::Print { keyword, expression } => interpret::print(interpreter, keyword, expression),
+            Stmt::Return { keyword, value } => interpret::r#return(interpreter, keyword, value),
+            Stmt::Var { keyword, name, initializer, doc } => interpret::var(interpreter, keyword, name, initializer, doc),
+            Stmt::While { keyword, condition, body } => interpret::r#while(interpreter, keyword, condition, body),
+        }
+    }
+}
+
+impl Resolve for Expr {
+    fn resolve(&self, resolver: &mut Resolver) {
+        match self {
+            Expr::Assign { name, value, depth } => resolve::assign(resolver, name, value, depth),
+            Expr::Binary { left, operator, right } => resolve::binary(resolver, left, operator, right),
+            Expr::Call { callee, paren, arguments } => resolve::call(resolver, callee, paren, arguments),
+            Expr::Get { object, name } => resolve::get(resolver, object, name),
+            Expr::Grouping { left_paren, expression, right_paren } => resolve::grouping(resolver, left_paren, expression, right_paren),
+            Expr::Interpolation { start, parts, end } => resolve::interpolation(resolver, start, parts, end),
+            Expr::Literal { value, token } => resolve::literal(resolver, value, token),
+            Expr::Logical { left, operator, right } => resolve::logical(resolver, left, operator, right),
+            Expr::Set { object, name, value } => resolve::set(resolver, object, name, value),
+            Expr::Super { keyword, method, depth } => resolve::super_(resolver, keyword, method, depth),
+            Expr::This { keyword, depth } => resolve::this(resolver, keyword, depth),
+            Expr::Unary { operator, right } => resolve::unary(resolver, operator, right),
+            Expr::Variable { name, depth } => resolve::variable(resolver, name, depth),
+        }
+    }
+}
+
+impl Resolve for Stmt {
+    fn resolve(&self, resolver: &mut Resolver) {
+        match self {
+            Stmt::Block { left_brace, statements, right_brace } => resolve::block(resolver, left_brace, statements, right_brace),
+            Stmt::Class { keyword, name, superclass, methods, right_brace, doc } => resolve::class(resolver, keyword, name, superclass, methods, right_brace, doc),
+            Stmt::Expression { expression } => resolve::expression(resolver, expression),
+            Stmt::Function { name, params, body, right_brace, doc } => resolve::function(resolver, name, params, body, right_brace, doc),
+            Stmt::If { keyword, condition, then_branch, else_branch } => resolve::r#if(resolver, keyword, condition, then_branch, else_branch),
+            Stmt::Print { keyword, expression } => resolve::print(resolver, keyword, expression),
+            Stmt::Return { keyword, value } => resolve::r#return(resolver, keyword, value),
+            Stmt::Var { keyword, name, initializer, doc } => resolve::var(resolver, keyword, name, initializer, doc),
+            Stmt::While { keyword, condition, body } => resolve::r#while(resolver, keyword, condition, body),
+        }
+    }
+}
+
+impl Spanned for Expr {
+    fn spanned(&self) -> Option<Span> {
+        match self {
+            Expr::Assign { name, value, depth } => spanned::assign(name, value, depth),
+            Expr::Binary { left, operator, right } => spanned::binary(left, operator, right),
+            Expr::Call { callee, paren, arguments } => spanned::call(callee, paren, arguments),
+            Expr::Get { object, name } => spanned::get(object, name),
+            Expr::Grouping { left_paren, expression, right_paren } => spanned::grouping(left_paren, expression, right_paren),
+            Expr::Interpolation { start, parts, end } => spanned::interpolation(start, parts, end),
+            Expr::Literal { value, token } => spanned::literal(value, token),
+            Expr::Logical { left, operator, right } => spanned::logical(left, operator, right),
+            Expr::Set { object, name, value } => spanned::set(object, name, value),
+            Expr::Super { keyword, method, depth } => spanned::super_(keyword, method, depth),
+            Expr::This { keyword, depth } => spanned::this(keyword, depth),
+            Expr::Unary { operator, right } => spanned::unary(operator, right),
+            Expr::Variable { name, depth } => spanned::variable(name, depth),
+        }
+    }
+}
+
+impl Spanned for Stmt {
+    fn spanned(&self) -> Option<Span> {
         match self {
-            Stmt::Block { statements } => interpret::block(statements),
-            Stmt::Expression { expression } => interpret::expression(expression),
-            Stmt::Print { expression } => interpret::print(expression),
-            Stmt::Var { name, initializer } => interpret::var(name, initializer),
+            Stmt::Block { left_brace, statements, right_brace } => spanned::block(left_brace, statements, right_brace),
+            Stmt::Class { keyword, name, superclass, methods, right_brace, doc } => spanned::class(keyword, name, superclass, methods, right_brace, doc),
+            Stmt::Expression { expression } => spanned::expression(expression),
+            Stmt::Function { name, params, body, right_brace, doc } => spanned::function(name, params, body, right_brace, doc),
+            Stmt::If { keyword, condition, then_branch, else_branch } => spanned::r#if(keyword, condition, then_branch, else_branch),
+            Stmt::Print { keyword, expression } => spanned::print(keyword, expression),
+            Stmt::Return { keyword, value } => spanned::r#return(keyword, value),
+            Stmt::Var { keyword, name, initializer, doc } => spanned::var(keyword, name, initializer, doc),
+            Stmt::While { keyword, condition, body } => spanned::r#while(keyword, condition, body),
         }
     }
 }
@@ -1,58 +1,426 @@
-use crate::token_type::Token;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::callable::{LoxCallable, LoxFunction};
+use crate::class::{LoxClass, LoxInstance};
+use crate::diagnostics::codes;
+use crate::environment::Environment;
+use crate::interpreter::{Interpreter, MAX_CALL_DEPTH};
+use crate::token_type::{Token, TokenType};
 use crate::types::expr::Expr;
 use crate::types::stmt::Stmt;
+use crate::types::value::{cmp_int_float, Value};
 use crate::types::Literal;
 
 use super::Interpret;
 
 #[derive(Debug)]
-pub struct RuntimeError(pub String);
+pub struct RuntimeError {
+    pub code: &'static str,
+    pub token: Box<Token>,
+    pub message: String,
+}
+
+impl RuntimeError {
+    pub fn new(code: &'static str, token: &Token, message: &str) -> RuntimeError {
+        RuntimeError {
+            code,
+            token: Box::new(token.to_owned()),
+            message: message.to_owned(),
+        }
+    }
+}
 
-pub fn assign(name: &Token, value: &Box<Expr>) -> Literal {
-    todo!()
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[line {}] Runtime error: {}", self.token.line(), self.message)
+    }
 }
 
-pub fn binary(left: &Box<Expr>, operator: &Token, right: &Box<Expr>) -> Literal {
-    todo!()
+impl std::error::Error for RuntimeError {}
+
+/// Anything that stops a statement from completing normally, a `return` travels
+/// up the same path as an error until the enclosing call catches it
+#[derive(Debug)]
+pub enum Unwind {
+    Error(RuntimeError),
+    Return(Value),
 }
 
-pub fn grouping(expression: &Box<Expr>) -> Literal {
-    return expression.interpret();
+impl From<RuntimeError> for Unwind {
+    fn from(err: RuntimeError) -> Self {
+        Unwind::Error(err)
+    }
 }
 
-pub fn literal(value: &Literal) -> Literal {
-    todo!()
+pub fn assign(interpreter: &mut Interpreter, name: &Token, value: &Box<Expr>, depth: &Cell<Option<usize>>) -> Result<Value, Unwind> {
+    let value = value.interpret(interpreter)?;
+    interpreter.assign_variable(name, depth.get(), value.clone())?;
+    Ok(value)
 }
 
-pub fn unary(operator: &Token, _right: &Box<Expr>) -> Literal {
-    let right = _right.interpret();
+pub fn binary(interpreter: &mut Interpreter, left: &Box<Expr>, operator: &Token, right: &Box<Expr>) -> Result<Value, Unwind> {
+    let left = left.interpret(interpreter)?;
+    let right = right.interpret(interpreter)?;
+
     match operator.token_type {
-        token_type::TokenType::MINUS => match right {
-            Literal::String(_) => RuntimeError("Invalid Cast: Cannot negate String"),
-            Literal::Number(num) => -num,
-            Literal::Boolean(_) => RuntimeError("Invalid Cast: Cannot negate Boolean, use '!'"),
-            Literal::None() => RuntimeError("Invalid Cast: Cannot negate Empty value"),
+        TokenType::MINUS => Ok(arithmetic(operator, &left, &right, i64::checked_sub, |l, r| l - r)?),
+        TokenType::STAR => Ok(arithmetic(operator, &left, &right, i64::checked_mul, |l, r| l * r)?),
+        // `/` always divides exactly, `~/` is the one that truncates
+        TokenType::SLASH => {
+            let (l, r) = number_operands(operator, &left, &right)?.floats();
+            Ok(Value::Float(l / r))
+        }
+        TokenType::TILDE_SLASH => {
+            nonzero_divisor(operator, &left, &right)?;
+            Ok(arithmetic(operator, &left, &right, i64::checked_div, |l, r| (l / r).trunc())?)
+        }
+        TokenType::PERCENT => {
+            nonzero_divisor(operator, &left, &right)?;
+            Ok(arithmetic(operator, &left, &right, i64::checked_rem, |l, r| l % r)?)
+        }
+        TokenType::PLUS => match (&left, &right) {
+            (Value::String(l), Value::String(r)) => Ok(Value::String(format!("{}{}", l, r).into())),
+            _ if left.is_number() && right.is_number() => {
+                Ok(arithmetic(operator, &left, &right, i64::checked_add, |l, r| l + r)?)
+            }
+            _ => Err(RuntimeError::new(codes::INVALID_OPERAND, operator, "Operands must be two numbers or two strings").into()),
         },
-        _ => !panic("INvalid unary token"),
+        TokenType::GREATER => Ok(Value::Boolean(compare(operator, &left, &right)?.is_some_and(Ordering::is_gt))),
+        TokenType::GREATER_EQUAL => Ok(Value::Boolean(compare(operator, &left, &right)?.is_some_and(Ordering::is_ge))),
+        TokenType::LESS => Ok(Value::Boolean(compare(operator, &left, &right)?.is_some_and(Ordering::is_lt))),
+        TokenType::LESS_EQUAL => Ok(Value::Boolean(compare(operator, &left, &right)?.is_some_and(Ordering::is_le))),
+        TokenType::BANG_EQUAL => Ok(Value::Boolean(left != right)),
+        TokenType::EQUAL_EQUAL => Ok(Value::Boolean(left == right)),
+        _ => Err(RuntimeError::new(codes::INTERNAL, operator, "Invalid binary operator").into()),
     }
 }
 
-pub fn variable(name: &Token) -> Literal {
-    todo!()
+pub fn call(interpreter: &mut Interpreter, callee: &Box<Expr>, paren: &Token, arguments: &Vec<Expr>) -> Result<Value, Unwind> {
+    let callee = callee.interpret(interpreter)?;
+
+    let mut values = Vec::with_capacity(arguments.len());
+    for argument in arguments {
+        values.push(argument.interpret(interpreter)?);
+    }
+
+    let function: Rc<dyn LoxCallable> = match callee {
+        Value::Callable(function) => function,
+        Value::Class(class) => class,
+        _ => return Err(RuntimeError::new(codes::NOT_CALLABLE, paren, "Can only call functions and classes").into()),
+    };
+
+    if values.len() != function.arity() {
+        let message = format!("Expected {} arguments but got {}", function.arity(), values.len());
+        return Err(RuntimeError::new(codes::ARITY_MISMATCH, paren, &message).into());
+    }
+
+    if interpreter.call_depth >= MAX_CALL_DEPTH {
+        return Err(RuntimeError::new(codes::STACK_OVERFLOW, paren, "Stack overflow").into());
+    }
+    interpreter.call_depth += 1;
+    let result = function.call(interpreter, values);
+    interpreter.call_depth -= 1;
+    result
 }
 
-pub fn block(statements: &Vec<Stmt>) -> Literal {
-    todo!()
+pub fn get(interpreter: &mut Interpreter, object: &Box<Expr>, name: &Token) -> Result<Value, Unwind> {
+    match object.interpret(interpreter)? {
+        Value::Instance(instance) => Ok(LoxInstance::get(&instance, name)?),
+        _ => Err(RuntimeError::new(codes::NOT_AN_INSTANCE, name, "Only instances have properties").into()),
+    }
+}
+
+pub fn grouping(interpreter: &mut Interpreter, _left_paren: &Token, expression: &Box<Expr>, _right_paren: &Token) -> Result<Value, Unwind> {
+    expression.interpret(interpreter)
+}
+
+/// Every part is stringified the way `print` shows it and the results joined
+pub fn interpolation(interpreter: &mut Interpreter, _start: &Token, parts: &Vec<Expr>, _end: &Token) -> Result<Value, Unwind> {
+    let mut string = String::new();
+    for part in parts {
+        string.push_str(&part.interpret(interpreter)?.to_string());
+    }
+    Ok(Value::String(string.into()))
+}
+
+pub fn literal(_interpreter: &mut Interpreter, value: &Literal, _token: &Token) -> Result<Value, Unwind> {
+    Ok(Value::from(value))
+}
+
+/// Short-circuits, handing back whichever operand decided the result rather than a boolean
+pub fn logical(interpreter: &mut Interpreter, left: &Box<Expr>, operator: &Token, right: &Box<Expr>) -> Result<Value, Unwind> {
+    let left = left.interpret(interpreter)?;
+
+    if operator.token_type == TokenType::OR {
+        if left.is_truthy() {
+            return Ok(left);
+        }
+    } else if !left.is_truthy() {
+        return Ok(left);
+    }
+
+    right.interpret(interpreter)
+}
+
+pub fn set(interpreter: &mut Interpreter, object: &Box<Expr>, name: &Token, value: &Box<Expr>) -> Result<Value, Unwind> {
+    let Value::Instance(instance) = object.interpret(interpreter)? else {
+        return Err(RuntimeError::new(codes::NOT_AN_INSTANCE, name, "Only instances have fields").into());
+    };
+
+    let value = value.interpret(interpreter)?;
+    instance.borrow_mut().set(name, value.clone());
+    Ok(value)
+}
+
+pub fn super_(interpreter: &mut Interpreter, keyword: &Token, method: &Token, depth: &Cell<Option<usize>>) -> Result<Value, Unwind> {
+    let distance = depth.get().ok_or_else(|| RuntimeError::new(codes::INTERNAL, keyword, "Unresolved 'super'"))?;
+
+    let Value::Class(superclass) = Environment::get_at(&interpreter.environment, distance, keyword)? else {
+        return Err(RuntimeError::new(codes::INTERNAL, keyword, "'super' is not a class").into());
+    };
+    // `this` always lives in the scope just inside the one holding `super`
+    let Some(Value::Instance(instance)) =
+        Environment::ancestor(&interpreter.environment, distance - 1).borrow().get_value("this")
+    else {
+        return Err(RuntimeError::new(codes::INTERNAL, keyword, "'this' is not an instance").into());
+    };
+
+    match superclass.find_method(&method.lexeme) {
+        Some(function) => Ok(Value::Callable(Rc::new(function.bind(instance)))),
+        None => Err(RuntimeError::new(codes::UNDEFINED_PROPERTY, method, &format!("Undefined property '{}'", method.lexeme)).into()),
+    }
+}
+
+pub fn this(interpreter: &mut Interpreter, keyword: &Token, depth: &Cell<Option<usize>>) -> Result<Value, Unwind> {
+    Ok(interpreter.look_up_variable(keyword, depth.get())?)
+}
+
+pub fn unary(interpreter: &mut Interpreter, operator: &Token, _right: &Box<Expr>) -> Result<Value, Unwind> {
+    let right = _right.interpret(interpreter)?;
+    match operator.token_type {
+        TokenType::MINUS => match right {
+            Value::String(_) => Err(RuntimeError::new(codes::INVALID_OPERAND, operator, "Invalid Cast: Cannot negate String").into()),
+            Value::Int(num) => num.checked_neg().map(Value::Int).ok_or_else(|| overflow(operator).into()),
+            Value::Float(num) => Ok(Value::Float(-num)),
+            Value::Boolean(_) => Err(RuntimeError::new(codes::INVALID_OPERAND, operator, "Invalid Cast: Cannot negate Boolean, use '!'").into()),
+            Value::Nil => Err(RuntimeError::new(codes::INVALID_OPERAND, operator, "Invalid Cast: Cannot negate Empty value").into()),
+            Value::Callable(_) | Value::Class(_) | Value::Instance(_) => {
+                Err(RuntimeError::new(codes::INVALID_OPERAND, operator, "Invalid Cast: Cannot negate an object").into())
+            }
+        },
+        TokenType::BANG => Ok(Value::Boolean(!right.is_truthy())),
+        _ => Err(RuntimeError::new(codes::INTERNAL, operator, "Invalid unary operator").into()),
+    }
 }
 
-pub fn expression(expression: &Box<Expr>) -> Literal {
-    todo!()
+pub fn variable(interpreter: &mut Interpreter, name: &Token, depth: &Cell<Option<usize>>) -> Result<Value, Unwind> {
+    Ok(interpreter.look_up_variable(name, depth.get())?)
 }
 
-pub fn print(expression: &Box<Expr>) -> Literal {
-    todo!()
+pub fn block(interpreter: &mut Interpreter, _left_brace: &Token, statements: &Vec<Stmt>, _right_brace: &Token) -> Result<Value, Unwind> {
+    let environment = Environment::with_enclosing(interpreter.environment.clone());
+    interpreter.execute_block(statements, environment)
+}
+
+pub fn class(
+    interpreter: &mut Interpreter,
+    _keyword: &Token,
+    name: &Token,
+    superclass: &Option<Box<Expr>>,
+    methods: &Vec<Stmt>,
+    _right_brace: &Token,
+    _doc: &Option<Rc<str>>,
+) -> Result<Value, Unwind> {
+    let superclass = match superclass {
+        Some(expr) => match expr.interpret(interpreter)? {
+            Value::Class(class) => Some(class),
+            _ => {
+                let token = match expr.as_ref() {
+                    Expr::Variable { name, .. } => name,
+                    _ => name,
+                };
+                return Err(RuntimeError::new(codes::INVALID_SUPERCLASS, token, "Superclass must be a class").into());
+            }
+        },
+        None => None,
+    };
+
+    interpreter.environment.borrow_mut().define(&name.lexeme, Value::Nil);
+
+    // methods of a subclass close over an extra scope holding `super`
+    let enclosing = interpreter.environment.clone();
+    if let Some(superclass) = &superclass {
+        let mut environment = Environment::with_enclosing(enclosing.clone());
+        environment.define("super", Value::Class(superclass.clone()));
+        interpreter.environment = Rc::new(RefCell::new(environment));
+    }
+
+    let mut functions = HashMap::new();
+    for method in methods {
+        if let Stmt::Function { name, params, body, .. } = method {
+            let function = LoxFunction::new(
+                name,
+                params,
+                body,
+                interpreter.environment.clone(),
+                name.lexeme == "init",
+            );
+            functions.insert(name.lexeme.to_string(), Rc::new(function));
+        }
+    }
+
+    let class = LoxClass::new(&name.lexeme, superclass, functions);
+    interpreter.environment = enclosing;
+    interpreter
+        .environment
+        .borrow_mut()
+        .assign(name, Value::Class(Rc::new(class)))?;
+    Ok(Value::Nil)
+}
+
+pub fn expression(interpreter: &mut Interpreter, expression: &Box<Expr>) -> Result<Value, Unwind> {
+    expression.interpret(interpreter)?;
+    Ok(Value::Nil)
+}
+
+pub fn function(
+    interpreter: &mut Interpreter,
+    name: &Token,
+    params: &Rc<Vec<Token>>,
+    body: &Rc<Vec<Stmt>>,
+    _right_brace: &Token,
+    _doc: &Option<Rc<str>>,
+) -> Result<Value, Unwind> {
+    let function = LoxFunction::new(name, params, body, interpreter.environment.clone(), false);
+    interpreter
+        .environment
+        .borrow_mut()
+        .define(&name.lexeme, Value::Callable(Rc::new(function)));
+    Ok(Value::Nil)
+}
+
+pub fn r#if(
+    interpreter: &mut Interpreter,
+    _keyword: &Token,
+    condition: &Box<Expr>,
+    then_branch: &Box<Stmt>,
+    else_branch: &Option<Box<Stmt>>,
+) -> Result<Value, Unwind> {
+    if condition.interpret(interpreter)?.is_truthy() {
+        then_branch.interpret(interpreter)?;
+    } else if let Some(else_branch) = else_branch {
+        else_branch.interpret(interpreter)?;
+    }
+    Ok(Value::Nil)
+}
+
+pub fn print(interpreter: &mut Interpreter, _keyword: &Token, expression: &Box<Expr>) -> Result<Value, Unwind> {
+    let value = expression.interpret(interpreter)?;
+    println!("{}", value);
+    Ok(Value::Nil)
+}
+
+pub fn r#return(interpreter: &mut Interpreter, _keyword: &Token, value: &Option<Box<Expr>>) -> Result<Value, Unwind> {
+    let value = match value {
+        Some(value) => value.interpret(interpreter)?,
+        None => Value::Nil,
+    };
+    Err(Unwind::Return(value))
+}
+
+pub fn var(interpreter: &mut Interpreter, _keyword: &Token, name: &Token, initializer: &Option<Box<Expr>>, _doc: &Option<Rc<str>>) -> Result<Value, Unwind> {
+    let value = match initializer {
+        Some(init) => init.interpret(interpreter)?,
+        None => Value::Nil,
+    };
+    interpreter.environment.borrow_mut().define(&name.lexeme, value);
+    Ok(Value::Nil)
+}
+
+pub fn r#while(interpreter: &mut Interpreter, _keyword: &Token, condition: &Box<Expr>, body: &Box<Stmt>) -> Result<Value, Unwind> {
+    while condition.interpret(interpreter)?.is_truthy() {
+        body.interpret(interpreter)?;
+    }
+    Ok(Value::Nil)
+}
+
+// helpers
+
+/// A pair of numeric operands, ints only stay ints when both sides are
+enum Operands {
+    Int(i64, i64),
+    Float(f64, f64),
+}
+
+impl Operands {
+    fn floats(self) -> (f64, f64) {
+        match self {
+            Operands::Int(l, r) => (l as f64, r as f64),
+            Operands::Float(l, r) => (l, r),
+        }
+    }
+}
+
+fn number_operands(operator: &Token, left: &Value, right: &Value) -> Result<Operands, RuntimeError> {
+    match (left, right) {
+        (Value::Int(l), Value::Int(r)) => Ok(Operands::Int(*l, *r)),
+        (Value::Int(l), Value::Float(r)) => Ok(Operands::Float(*l as f64, *r)),
+        (Value::Float(l), Value::Int(r)) => Ok(Operands::Float(*l, *r as f64)),
+        (Value::Float(l), Value::Float(r)) => Ok(Operands::Float(*l, *r)),
+        _ => {
+            let message = format!(
+                "Operands of '{}' must be numbers, got {} and {}",
+                operator.lexeme,
+                left.type_name(),
+                right.type_name()
+            );
+            Err(RuntimeError::new(codes::INVALID_OPERAND, operator, &message))
+        }
+    }
+}
+
+/// Applies `int_op` when both operands are ints, where `None` means it overflowed,
+/// and `float_op` otherwise
+fn arithmetic(
+    operator: &Token,
+    left: &Value,
+    right: &Value,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<Value, RuntimeError> {
+    match number_operands(operator, left, right)? {
+        Operands::Int(l, r) => int_op(l, r).map(Value::Int).ok_or_else(|| overflow(operator)),
+        Operands::Float(l, r) => Ok(Value::Float(float_op(l, r))),
+    }
+}
+
+/// `None` when either side is NaN
+fn compare(operator: &Token, left: &Value, right: &Value) -> Result<Option<Ordering>, RuntimeError> {
+    // mixed pairs are compared exactly rather than promoting the int
+    match (left, right) {
+        (Value::Int(l), Value::Float(r)) => return Ok(cmp_int_float(*l, *r)),
+        (Value::Float(l), Value::Int(r)) => return Ok(cmp_int_float(*r, *l).map(Ordering::reverse)),
+        _ => {}
+    }
+    match number_operands(operator, left, right)? {
+        Operands::Int(l, r) => Ok(Some(l.cmp(&r))),
+        Operands::Float(l, r) => Ok(l.partial_cmp(&r)),
+    }
+}
+
+/// Integer `~/` and `%` have no answer for zero, float ones give infinity or NaN
+fn nonzero_divisor(operator: &Token, left: &Value, right: &Value) -> Result<(), RuntimeError> {
+    if let (Value::Int(_), Value::Int(0)) = (left, right) {
+        return Err(RuntimeError::new(codes::DIVISION_BY_ZERO, operator, "Division by zero"));
+    }
+    Ok(())
 }
 
-pub fn var(name: &Token, initializer: &Box<Expr>) -> Literal {
-    todo!()
+fn overflow(operator: &Token) -> RuntimeError {
+    let message = format!("Integer overflow evaluating '{}'", operator.lexeme);
+    RuntimeError::new(codes::INTEGER_OVERFLOW, operator, &message)
 }
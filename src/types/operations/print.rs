@@ -1,3 +1,6 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
 use crate::token_type::Token;
 use crate::types::expr::Expr;
 use crate::types::stmt::Stmt;
@@ -5,59 +8,142 @@ use crate::types::Literal;
 
 use super::Print;
 
-pub fn assign(name: &Token, value: &Box<Expr>) -> String {
-    todo!()
+pub fn assign(name: &Token, value: &Box<Expr>, _depth: &Cell<Option<usize>>) -> String {
+    parenthesize(&format!("= {}", name.lexeme), vec![value])
 }
 
 pub fn binary(left: &Box<Expr>, operator: &Token, right: &Box<Expr>) -> String {
     parenthesize(&operator.lexeme, vec![left, right])
 }
 
-pub fn grouping(expression: &Box<Expr>) -> String {
+pub fn call(callee: &Box<Expr>, _paren: &Token, arguments: &Vec<Expr>) -> String {
+    let mut exprs: Vec<&Expr> = vec![callee];
+    exprs.extend(arguments.iter());
+    parenthesize("call", exprs)
+}
+
+pub fn get(object: &Box<Expr>, name: &Token) -> String {
+    parenthesize(&format!(". {}", name.lexeme), vec![object])
+}
+
+pub fn grouping(_left_paren: &Token, expression: &Box<Expr>, _right_paren: &Token) -> String {
     parenthesize("grouping", vec![expression])
 }
 
-pub fn literal(value: &Literal) -> String {
+pub fn interpolation(_start: &Token, parts: &Vec<Expr>, _end: &Token) -> String {
+    let ls: Vec<String> = parts.iter().map(|part| part.print()).collect();
+    format!("( interpolate {} )", ls.join(" "))
+}
+
+pub fn literal(value: &Literal, _token: &Token) -> String {
     match value {
         Literal::String(s) => s.to_string(),
-        Literal::Number(n) => n.to_string(),
+        Literal::Int(n) => n.to_string(),
+        Literal::Float(n) => n.to_string(),
         Literal::Boolean(true) => "true".to_string(),
         Literal::Boolean(false) => "false".to_string(),
         Literal::None() => "nil".to_string(),
     }
 }
 
+pub fn logical(left: &Box<Expr>, operator: &Token, right: &Box<Expr>) -> String {
+    parenthesize(&operator.lexeme, vec![left, right])
+}
+
+pub fn set(object: &Box<Expr>, name: &Token, value: &Box<Expr>) -> String {
+    parenthesize(&format!("= .{}", name.lexeme), vec![object, value])
+}
+
+pub fn super_(_keyword: &Token, method: &Token, _depth: &Cell<Option<usize>>) -> String {
+    format!("super.{}", method.lexeme)
+}
+
+pub fn this(_keyword: &Token, _depth: &Cell<Option<usize>>) -> String {
+    "this".to_string()
+}
+
 pub fn unary(operator: &Token, right: &Box<Expr>) -> String {
     parenthesize(&operator.lexeme, vec![&right])
 }
 
-pub fn variable(name: &Token) -> String {
-    todo!()
+pub fn variable(name: &Token, _depth: &Cell<Option<usize>>) -> String {
+    name.lexeme.to_string()
 }
 
-pub fn block(statements: &Vec<Stmt>) -> String {
-    todo!()
+pub fn block(_left_brace: &Token, statements: &Vec<Stmt>, _right_brace: &Token) -> String {
+    block_of(statements)
+}
+
+pub fn class(_keyword: &Token, name: &Token, superclass: &Option<Box<Expr>>, methods: &Vec<Stmt>, _right_brace: &Token, _doc: &Option<Rc<str>>) -> String {
+    let ls: String = methods
+        .iter()
+        .map(|method| method.print())
+        .collect::<Vec<String>>()
+        .join(" ");
+    match superclass {
+        Some(superclass) => format!("( class {} < {} {} )", name.lexeme, superclass.print(), ls),
+        None => format!("( class {} {} )", name.lexeme, ls),
+    }
 }
 
 pub fn expression(expression: &Box<Expr>) -> String {
-    todo!()
+    parenthesize(";", vec![expression])
+}
+
+pub fn function(name: &Token, params: &Rc<Vec<Token>>, body: &Rc<Vec<Stmt>>, _right_brace: &Token, _doc: &Option<Rc<str>>) -> String {
+    let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+    format!("( fun {}({}) {} )", name.lexeme, params.join(", "), block_of(body))
+}
+
+pub fn r#if(_keyword: &Token, condition: &Box<Expr>, then_branch: &Box<Stmt>, else_branch: &Option<Box<Stmt>>) -> String {
+    match else_branch {
+        Some(else_branch) => format!(
+            "( if {} {} else {} )",
+            condition.print(),
+            then_branch.print(),
+            else_branch.print()
+        ),
+        None => format!("( if {} {} )", condition.print(), then_branch.print()),
+    }
 }
 
-pub fn print(expression: &Box<Expr>) -> String {
-    todo!()
+pub fn print(_keyword: &Token, expression: &Box<Expr>) -> String {
+    parenthesize("print", vec![expression])
 }
 
-pub fn var(name: &Token, initializer: &Box<Expr>) -> String {
-    todo!()
+pub fn r#return(_keyword: &Token, value: &Option<Box<Expr>>) -> String {
+    match value {
+        Some(value) => parenthesize("return", vec![value]),
+        None => "( return )".to_string(),
+    }
+}
+
+pub fn var(_keyword: &Token, name: &Token, initializer: &Option<Box<Expr>>, _doc: &Option<Rc<str>>) -> String {
+    match initializer {
+        Some(init) => parenthesize(&format!("var {} =", name.lexeme), vec![init]),
+        None => format!("( var {} )", name.lexeme),
+    }
+}
+
+pub fn r#while(_keyword: &Token, condition: &Box<Expr>, body: &Box<Stmt>) -> String {
+    format!("( while {} {} )", condition.print(), body.print())
+}
+
+fn block_of(statements: &[Stmt]) -> String {
+    let ls: String = statements
+        .iter()
+        .map(|stmt| stmt.print())
+        .collect::<Vec<String>>()
+        .join(" ");
+    format!("( block {} )", ls)
 }
 
 fn parenthesize(name: &str, exprs: Vec<&Expr>) -> String {
     let ls: String = exprs
         .iter()
         .map(|expr| {
-            let ret = expr.print();
             // println!("{}", &ret);
-            return ret;
+            expr.print()
         })
         .collect::<Vec<String>>()
         .join(" ");
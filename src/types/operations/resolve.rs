@@ -0,0 +1,177 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::diagnostics::codes;
+use crate::resolver::{ClassType, FunctionType, Resolver};
+use crate::token_type::Token;
+use crate::types::expr::Expr;
+use crate::types::stmt::Stmt;
+use crate::types::Literal;
+
+use super::Resolve;
+
+pub fn assign(resolver: &mut Resolver, name: &Token, value: &Box<Expr>, depth: &Cell<Option<usize>>) {
+    value.resolve(resolver);
+    resolver.resolve_local(name, depth);
+}
+
+pub fn binary(resolver: &mut Resolver, left: &Box<Expr>, _operator: &Token, right: &Box<Expr>) {
+    left.resolve(resolver);
+    right.resolve(resolver);
+}
+
+pub fn call(resolver: &mut Resolver, callee: &Box<Expr>, _paren: &Token, arguments: &Vec<Expr>) {
+    callee.resolve(resolver);
+    for argument in arguments {
+        argument.resolve(resolver);
+    }
+}
+
+pub fn get(resolver: &mut Resolver, object: &Box<Expr>, _name: &Token) {
+    object.resolve(resolver);
+}
+
+pub fn grouping(resolver: &mut Resolver, _left_paren: &Token, expression: &Box<Expr>, _right_paren: &Token) {
+    expression.resolve(resolver);
+}
+
+pub fn interpolation(resolver: &mut Resolver, _start: &Token, parts: &Vec<Expr>, _end: &Token) {
+    for part in parts {
+        part.resolve(resolver);
+    }
+}
+
+pub fn literal(_resolver: &mut Resolver, _value: &Literal, _token: &Token) {}
+
+pub fn logical(resolver: &mut Resolver, left: &Box<Expr>, _operator: &Token, right: &Box<Expr>) {
+    left.resolve(resolver);
+    right.resolve(resolver);
+}
+
+pub fn set(resolver: &mut Resolver, object: &Box<Expr>, _name: &Token, value: &Box<Expr>) {
+    value.resolve(resolver);
+    object.resolve(resolver);
+}
+
+pub fn super_(resolver: &mut Resolver, keyword: &Token, _method: &Token, depth: &Cell<Option<usize>>) {
+    match resolver.current_class {
+        ClassType::None => resolver.error(codes::SUPER_OUTSIDE_CLASS, keyword, "Can't use 'super' outside of a class"),
+        ClassType::Class => resolver.error(codes::SUPER_WITHOUT_SUPERCLASS, keyword, "Can't use 'super' in a class with no superclass"),
+        ClassType::Subclass => resolver.resolve_local(keyword, depth),
+    }
+}
+
+pub fn this(resolver: &mut Resolver, keyword: &Token, depth: &Cell<Option<usize>>) {
+    if resolver.current_class == ClassType::None {
+        resolver.error(codes::THIS_OUTSIDE_CLASS, keyword, "Can't use 'this' outside of a class");
+        return;
+    }
+    resolver.resolve_local(keyword, depth);
+}
+
+pub fn unary(resolver: &mut Resolver, _operator: &Token, right: &Box<Expr>) {
+    right.resolve(resolver);
+}
+
+pub fn variable(resolver: &mut Resolver, name: &Token, depth: &Cell<Option<usize>>) {
+    if resolver.is_uninitialized(name) {
+        resolver.error(codes::READ_IN_OWN_INITIALIZER, name, "Can't read local variable in its own initializer");
+    }
+    resolver.resolve_local(name, depth);
+}
+
+pub fn block(resolver: &mut Resolver, _left_brace: &Token, statements: &Vec<Stmt>, _right_brace: &Token) {
+    resolver.begin_scope();
+    resolver.resolve_all(statements);
+    resolver.end_scope();
+}
+
+pub fn class(resolver: &mut Resolver, _keyword: &Token, name: &Token, superclass: &Option<Box<Expr>>, methods: &Vec<Stmt>, _right_brace: &Token, _doc: &Option<Rc<str>>) {
+    let enclosing_class = resolver.current_class;
+    resolver.current_class = ClassType::Class;
+
+    resolver.declare(name);
+    resolver.define(name);
+
+    if let Some(superclass) = superclass {
+        if let Expr::Variable { name: superclass_name, .. } = superclass.as_ref()
+            && superclass_name.lexeme == name.lexeme
+        {
+            resolver.error(codes::INHERIT_FROM_SELF, superclass_name, "A class can't inherit from itself");
+        }
+
+        resolver.current_class = ClassType::Subclass;
+        superclass.resolve(resolver);
+
+        resolver.begin_scope();
+        resolver.define_keyword("super");
+    }
+
+    resolver.begin_scope();
+    resolver.define_keyword("this");
+    for method in methods {
+        if let Stmt::Function { name, params, body, .. } = method {
+            let kind = if name.lexeme == "init" {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            resolver.resolve_function(params, body, kind);
+        }
+    }
+    resolver.end_scope();
+
+    if superclass.is_some() {
+        resolver.end_scope();
+    }
+
+    resolver.current_class = enclosing_class;
+}
+
+pub fn expression(resolver: &mut Resolver, expression: &Box<Expr>) {
+    expression.resolve(resolver);
+}
+
+pub fn function(resolver: &mut Resolver, name: &Token, params: &Rc<Vec<Token>>, body: &Rc<Vec<Stmt>>, _right_brace: &Token, _doc: &Option<Rc<str>>) {
+    // defined eagerly so the function can refer to itself recursively
+    resolver.declare(name);
+    resolver.define(name);
+    resolver.resolve_function(params, body, FunctionType::Function);
+}
+
+pub fn r#if(resolver: &mut Resolver, _keyword: &Token, condition: &Box<Expr>, then_branch: &Box<Stmt>, else_branch: &Option<Box<Stmt>>) {
+    condition.resolve(resolver);
+    then_branch.resolve(resolver);
+    if let Some(else_branch) = else_branch {
+        else_branch.resolve(resolver);
+    }
+}
+
+pub fn print(resolver: &mut Resolver, _keyword: &Token, expression: &Box<Expr>) {
+    expression.resolve(resolver);
+}
+
+pub fn r#return(resolver: &mut Resolver, keyword: &Token, value: &Option<Box<Expr>>) {
+    if resolver.current_function == FunctionType::None {
+        resolver.error(codes::TOP_LEVEL_RETURN, keyword, "Can't return from top-level code");
+    }
+    if let Some(value) = value {
+        if resolver.current_function == FunctionType::Initializer {
+            resolver.error(codes::RETURN_FROM_INITIALIZER, keyword, "Can't return a value from an initializer");
+        }
+        value.resolve(resolver);
+    }
+}
+
+pub fn var(resolver: &mut Resolver, _keyword: &Token, name: &Token, initializer: &Option<Box<Expr>>, _doc: &Option<Rc<str>>) {
+    resolver.declare(name);
+    if let Some(initializer) = initializer {
+        initializer.resolve(resolver);
+    }
+    resolver.define(name);
+}
+
+pub fn r#while(resolver: &mut Resolver, _keyword: &Token, condition: &Box<Expr>, body: &Box<Stmt>) {
+    condition.resolve(resolver);
+    body.resolve(resolver);
+}
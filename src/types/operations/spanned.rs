@@ -0,0 +1,109 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::span::Span;
+use crate::token_type::Token;
+use crate::types::expr::Expr;
+use crate::types::stmt::Stmt;
+use crate::types::Literal;
+
+use super::Spanned;
+
+pub fn assign(name: &Token, value: &Box<Expr>, _depth: &Cell<Option<usize>>) -> Option<Span> {
+    join(Some(name.span), value.spanned())
+}
+
+pub fn binary(left: &Box<Expr>, _operator: &Token, right: &Box<Expr>) -> Option<Span> {
+    join(left.spanned(), right.spanned())
+}
+
+pub fn call(callee: &Box<Expr>, paren: &Token, _arguments: &Vec<Expr>) -> Option<Span> {
+    join(callee.spanned(), Some(paren.span))
+}
+
+pub fn get(object: &Box<Expr>, name: &Token) -> Option<Span> {
+    join(object.spanned(), Some(name.span))
+}
+
+pub fn grouping(left_paren: &Token, _expression: &Box<Expr>, right_paren: &Token) -> Option<Span> {
+    Some(left_paren.span.to(right_paren.span))
+}
+
+pub fn interpolation(start: &Token, _parts: &Vec<Expr>, end: &Token) -> Option<Span> {
+    Some(start.span.to(end.span))
+}
+
+pub fn literal(_value: &Literal, token: &Token) -> Option<Span> {
+    Some(token.span)
+}
+
+pub fn logical(left: &Box<Expr>, _operator: &Token, right: &Box<Expr>) -> Option<Span> {
+    join(left.spanned(), right.spanned())
+}
+
+pub fn set(object: &Box<Expr>, _name: &Token, value: &Box<Expr>) -> Option<Span> {
+    join(object.spanned(), value.spanned())
+}
+
+pub fn super_(keyword: &Token, method: &Token, _depth: &Cell<Option<usize>>) -> Option<Span> {
+    Some(keyword.span.to(method.span))
+}
+
+pub fn this(keyword: &Token, _depth: &Cell<Option<usize>>) -> Option<Span> {
+    Some(keyword.span)
+}
+
+pub fn unary(operator: &Token, right: &Box<Expr>) -> Option<Span> {
+    join(Some(operator.span), right.spanned())
+}
+
+pub fn variable(name: &Token, _depth: &Cell<Option<usize>>) -> Option<Span> {
+    Some(name.span)
+}
+
+pub fn block(left_brace: &Token, _statements: &Vec<Stmt>, right_brace: &Token) -> Option<Span> {
+    Some(left_brace.span.to(right_brace.span))
+}
+
+pub fn class(keyword: &Token, _name: &Token, _superclass: &Option<Box<Expr>>, _methods: &Vec<Stmt>, right_brace: &Token, _doc: &Option<Rc<str>>) -> Option<Span> {
+    Some(keyword.span.to(right_brace.span))
+}
+
+pub fn expression(expression: &Box<Expr>) -> Option<Span> {
+    expression.spanned()
+}
+
+pub fn function(name: &Token, _params: &Rc<Vec<Token>>, _body: &Rc<Vec<Stmt>>, right_brace: &Token, _doc: &Option<Rc<str>>) -> Option<Span> {
+    Some(name.span.to(right_brace.span))
+}
+
+pub fn r#if(keyword: &Token, _condition: &Box<Expr>, then_branch: &Box<Stmt>, else_branch: &Option<Box<Stmt>>) -> Option<Span> {
+    let last = match else_branch {
+        Some(else_branch) => else_branch.spanned(),
+        None => then_branch.spanned(),
+    };
+    join(Some(keyword.span), last)
+}
+
+pub fn print(keyword: &Token, expression: &Box<Expr>) -> Option<Span> {
+    join(Some(keyword.span), expression.spanned())
+}
+
+pub fn r#return(keyword: &Token, value: &Option<Box<Expr>>) -> Option<Span> {
+    join(Some(keyword.span), value.as_ref().and_then(|value| value.spanned()))
+}
+
+pub fn var(keyword: &Token, name: &Token, initializer: &Option<Box<Expr>>, _doc: &Option<Rc<str>>) -> Option<Span> {
+    join(Some(keyword.span.to(name.span)), initializer.as_ref().and_then(|init| init.spanned()))
+}
+
+pub fn r#while(keyword: &Token, _condition: &Box<Expr>, body: &Box<Stmt>) -> Option<Span> {
+    join(Some(keyword.span), body.spanned())
+}
+
+fn join(first: Option<Span>, last: Option<Span>) -> Option<Span> {
+    match (first, last) {
+        (Some(first), Some(last)) => Some(first.to(last)),
+        (first, last) => first.or(last),
+    }
+}
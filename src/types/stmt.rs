@@ -1,10 +1,17 @@
+use std::rc::Rc;
+
 use crate::token_type::{Token};
 use crate::types::expr::Expr;
 
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
 pub enum Stmt {
-    Block{statements: Vec<Stmt>},
+    Block{left_brace: Token, statements: Vec<Stmt>, right_brace: Token},
+    Class{keyword: Token, name: Token, superclass: Option<Box<Expr>>, methods: Vec<Stmt>, right_brace: Token, doc: Option<Rc<str>>},
     Expression{expression: Box<Expr>},
-    Print{expression: Box<Expr>},
-    Var{name: Token, initializer: Box<Expr>},
+    Function{name: Token, params: Rc<Vec<Token>>, body: Rc<Vec<Stmt>>, right_brace: Token, doc: Option<Rc<str>>},
+    If{keyword: Token, condition: Box<Expr>, then_branch: Box<Stmt>, else_branch: Option<Box<Stmt>>},
+    Print{keyword: Token, expression: Box<Expr>},
+    Return{keyword: Token, value: Option<Box<Expr>>},
+    Var{keyword: Token, name: Token, initializer: Option<Box<Expr>>, doc: Option<Rc<str>>},
+    While{keyword: Token, condition: Box<Expr>, body: Box<Stmt>},
 }
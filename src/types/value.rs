@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::callable::LoxCallable;
+use crate::class::{LoxClass, LoxInstance};
+use crate::token_type::Literal;
+
+/// A runtime value produced by the interpreter, cloning is always cheap
+#[derive(Debug, Clone)]
+pub enum Value {
+    Nil,
+    Boolean(bool),
+    Int(i64),
+    Float(f64),
+    String(Rc<str>),
+    Callable(Rc<dyn LoxCallable>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<RefCell<LoxInstance>>),
+}
+
+impl Value {
+    /// `nil` and `false` are falsey, everything else is truthy
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Nil => false,
+            Value::Boolean(b) => *b,
+            _ => true,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Int(_) | Value::Float(_))
+    }
+
+    /// How the value's type is named in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Boolean(_) => "boolean",
+            Value::Int(_) | Value::Float(_) => "number",
+            Value::String(_) => "string",
+            Value::Callable(_) => "function",
+            Value::Class(_) => "class",
+            Value::Instance(_) => "instance",
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Boolean(l), Value::Boolean(r)) => l == r,
+            (Value::Int(l), Value::Int(r)) => l == r,
+            (Value::Float(l), Value::Float(r)) => l == r,
+            // numbers compare by value whichever type they are
+            (Value::Int(l), Value::Float(r)) | (Value::Float(r), Value::Int(l)) => {
+                cmp_int_float(*l, *r) == Some(Ordering::Equal)
+            }
+            (Value::String(l), Value::String(r)) => l == r,
+            // callables only equal themselves
+            (Value::Callable(l), Value::Callable(r)) => Rc::ptr_eq(l, r),
+            (Value::Class(l), Value::Class(r)) => Rc::ptr_eq(l, r),
+            (Value::Instance(l), Value::Instance(r)) => Rc::ptr_eq(l, r),
+            _ => false,
+        }
+    }
+}
+
+/// Orders an int against a float exactly, without rounding the int to the nearest float
+/// first, `None` when the float is NaN
+pub fn cmp_int_float(int: i64, float: f64) -> Option<Ordering> {
+    // 2^63, the first float past i64::MAX, -2^63 itself is i64::MIN
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    if float.is_nan() {
+        None
+    } else if float >= LIMIT {
+        Some(Ordering::Less)
+    } else if float < -LIMIT {
+        Some(Ordering::Greater)
+    } else {
+        let whole = float.trunc();
+        // a fraction only matters once the whole parts are equal
+        Some(int.cmp(&(whole as i64)).then(whole.partial_cmp(&float)?))
+    }
+}
+
+impl From<&Literal> for Value {
+    fn from(literal: &Literal) -> Self {
+        match literal {
+            Literal::String(s) => Value::String(Rc::from(s.as_str())),
+            Literal::Int(n) => Value::Int(*n),
+            Literal::Float(n) => Value::Float(*n),
+            Literal::Boolean(b) => Value::Boolean(*b),
+            Literal::None() => Value::Nil,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            // whole floats print without a fraction, like ints
+            Value::Float(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Callable(c) => write!(f, "{}", c),
+            Value::Class(c) => write!(f, "{}", c),
+            Value::Instance(i) => write!(f, "{}", i.borrow()),
+        }
+    }
+}
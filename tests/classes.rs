@@ -0,0 +1,106 @@
+mod common;
+
+use common::{first_error, output};
+
+#[test]
+fn instances_hold_their_own_fields() {
+    let lines = output(
+        r#"
+        class Point {}
+        var a = Point();
+        var b = Point();
+        a.x = 1;
+        b.x = 2;
+        a.y = a.x + 10;
+        print a.x;
+        print b.x;
+        print a.y;
+        print a;
+        print Point;
+        "#,
+    );
+    assert_eq!(lines, ["1", "2", "11", "Point instance", "Point"]);
+}
+
+#[test]
+fn methods_stay_bound_to_their_instance() {
+    let lines = output(
+        r#"
+        class Person {
+            sayName() {
+                print this.name;
+            }
+        }
+
+        var jane = Person();
+        jane.name = "Jane";
+        var bill = Person();
+        bill.name = "Bill";
+
+        var method = jane.sayName;
+        bill.sayName = method;
+        bill.sayName();
+        method();
+        "#,
+    );
+    assert_eq!(lines, ["Jane", "Jane"]);
+}
+
+#[test]
+fn init_runs_on_construction_and_returns_this() {
+    let lines = output(
+        r#"
+        class Counter {
+            init(start) {
+                this.count = start;
+                if (start > 10) return;
+                this.small = true;
+            }
+        }
+
+        var counter = Counter(3);
+        print counter.count;
+        print counter.small;
+        print counter.init(20) == counter;
+        print counter.count;
+        print Counter(11).count;
+        "#,
+    );
+    assert_eq!(lines, ["3", "true", "true", "20", "11"]);
+}
+
+#[test]
+fn init_checks_its_arity() {
+    let (status, error) = first_error("class A {\n  init(a, b) {}\n}\nA(1);\n");
+    assert_eq!(status, 70);
+    assert!(error.starts_with("error[E0305]: "), "error was `{}`", error);
+}
+
+#[test]
+fn returning_a_value_from_init_is_an_error() {
+    let (status, error) = first_error("class A {\n  init() {\n    return 1;\n  }\n}\n");
+    assert_eq!(status, 65);
+    assert_eq!(error, "error[E0204]: Can't return a value from an initializer");
+}
+
+#[test]
+fn this_outside_a_class_is_an_error() {
+    for source in ["print this;", "fun f() {\n  return this;\n}"] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 65, "running `{}`", source);
+        assert_eq!(error, "error[E0205]: Can't use 'this' outside of a class", "running `{}`", source);
+    }
+}
+
+#[test]
+fn missing_properties_and_non_instances_are_runtime_errors() {
+    for (source, message) in [
+        ("class A {}\nprint A().missing;", "error[E0303]: Undefined property 'missing'"),
+        ("var a = 1;\nprint a.field;", "error[E0306]: Only instances have properties"),
+        ("var a = \"s\";\na.field = 1;", "error[E0306]: Only instances have fields"),
+    ] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 70, "running `{}`", source);
+        assert_eq!(error, message, "running `{}`", source);
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::output;
+
+#[test]
+fn counter_keeps_its_own_state() {
+    let lines = output(
+        r#"
+        fun makeCounter() {
+            var i = 0;
+            fun count() {
+                i = i + 1;
+                print i;
+            }
+            return count;
+        }
+
+        var counter = makeCounter();
+        counter();
+        counter();
+
+        var other = makeCounter();
+        other();
+        counter();
+        "#,
+    );
+    assert_eq!(lines, ["1", "2", "1", "3"]);
+}
+
+#[test]
+fn closures_from_the_same_scope_share_variables() {
+    let lines = output(
+        r#"
+        var get;
+        var set;
+        fun make() {
+            var value = "before";
+            fun getter() { return value; }
+            fun setter(v) { value = v; }
+            get = getter;
+            set = setter;
+        }
+        make();
+        print get();
+        set("after");
+        print get();
+        "#,
+    );
+    assert_eq!(lines, ["before", "after"]);
+}
+
+#[test]
+fn closure_outlives_its_block() {
+    let lines = output(
+        r#"
+        var f;
+        {
+            var local = "captured";
+            fun show() { print local; }
+            f = show;
+        }
+        f();
+        "#,
+    );
+    assert_eq!(lines, ["captured"]);
+}
+
+#[test]
+fn callback_sees_arguments_of_its_factory() {
+    let lines = output(
+        r#"
+        fun adder(n) {
+            fun add(x) { return x + n; }
+            return add;
+        }
+        var addTwo = adder(2);
+        print addTwo(40);
+        "#,
+    );
+    assert_eq!(lines, ["42"]);
+}
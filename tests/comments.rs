@@ -0,0 +1,120 @@
+mod common;
+
+use common::{output, parse, run};
+use rlox::types::stmt::Stmt;
+
+/// The doc attached to a declaration, `None` for statements that cannot have one
+fn doc(stmt: &Stmt) -> Option<&str> {
+    match stmt {
+        Stmt::Class { doc, .. } | Stmt::Function { doc, .. } | Stmt::Var { doc, .. } => doc.as_deref(),
+        _ => None,
+    }
+}
+
+#[test]
+fn block_comments_nest() {
+    let lines = output(
+        r#"
+        /* outer /* inner */ still a comment */
+        print 1 /* between */ + 2;
+        /* /* /* deep */ */ */ print 4;
+        "#,
+    );
+    assert_eq!(lines, ["3", "4"]);
+}
+
+#[test]
+fn block_comments_count_lines() {
+    let run = run("/*\n/* two\n */\nthree\n*/\nprint nil + 1;\n");
+    assert_eq!(run.status, 70);
+    assert!(run.stderr.contains("\n6 | print nil + 1;\n"), "stderr was:\n{}", run.stderr);
+}
+
+#[test]
+fn unterminated_block_comment_is_a_scan_error() {
+    let run = run("print 1;\n/* open /* closed */\nprint 2;\n");
+    assert_eq!(run.status, 65);
+    assert_eq!(run.stdout, "");
+    let first_line = run.stderr.lines().next().unwrap_or_default();
+    assert_eq!(first_line, "error[E0003]: Unterminated block comment");
+    assert!(run.stderr.contains(":2:1\n"), "stderr was:\n{}", run.stderr);
+}
+
+#[test]
+fn doc_comments_do_not_change_behaviour() {
+    let lines = output(
+        r#"
+        /// Greets whoever is passed in
+        fun greet(name) {
+            /// not attached to anything that matters
+            print "hi " + name;
+        }
+        //// four slashes is a plain comment
+        greet("you");
+        "#,
+    );
+    assert_eq!(lines, ["hi you"]);
+}
+
+#[test]
+fn doc_comments_attach_to_the_following_declaration() {
+    let statements = parse(
+        r#"
+        /// Adds two numbers
+        fun add(a, b) { return a + b; }
+
+        /// A point in space
+        ///
+        ///   with indented lines kept
+        class Point {
+            /// Makes a point
+            init(x) { this.x = x; }
+
+            undocumented() {}
+        }
+
+        /// How many there are
+        var count = 0;
+        var plain = 1;
+        "#,
+    );
+    let docs: Vec<Option<&str>> = statements.iter().map(doc).collect();
+    assert_eq!(
+        docs,
+        [
+            Some("Adds two numbers"),
+            Some("A point in space\n\n  with indented lines kept"),
+            Some("How many there are"),
+            None,
+        ]
+    );
+
+    let Stmt::Class { methods, .. } = &statements[1] else {
+        panic!("expected a class");
+    };
+    let method_docs: Vec<Option<&str>> = methods.iter().map(doc).collect();
+    assert_eq!(method_docs, [Some("Makes a point"), None]);
+}
+
+#[test]
+fn plain_comments_are_not_docs() {
+    let statements = parse(
+        r#"
+        //// four slashes is a plain comment
+        fun a() {}
+        // two slashes too
+        var b;
+        /** block comments are never docs */
+        class C {}
+        "#,
+    );
+    let docs: Vec<Option<&str>> = statements.iter().map(doc).collect();
+    assert_eq!(docs, [None, None, None]);
+}
+
+#[test]
+fn docs_only_reach_the_next_token() {
+    let statements = parse("/// for the print, which cannot take it\nprint 1;\nvar a;\n/// only\n//// mixed in\n/// these\nvar b;\n");
+    let docs: Vec<Option<&str>> = statements.iter().map(doc).collect();
+    assert_eq!(docs, [None, None, Some("only\nthese")]);
+}
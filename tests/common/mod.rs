@@ -0,0 +1,87 @@
+// every test binary compiles its own copy and uses a different subset of it
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rlox::parser::Parser;
+use rlox::scanner::Scanner;
+use rlox::types::stmt::Stmt;
+
+static SCRIPT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// What a single run of the `rlox` binary produced
+pub struct Run {
+    pub stdout: String,
+    pub stderr: String,
+    pub status: i32,
+}
+
+/// Writes `source` to a scratch file and runs it through the `rlox` binary
+pub fn run(source: &str) -> Run {
+    run_with_args(&[], source)
+}
+
+/// Like [`run`], passing `args` before the script path
+pub fn run_with_args(args: &[&str], source: &str) -> Run {
+    let id = SCRIPT_COUNT.fetch_add(1, Ordering::SeqCst);
+    let path: PathBuf = std::env::temp_dir().join(format!("rlox-test-{}-{}.lox", std::process::id(), id));
+    fs::write(&path, source).expect("failed to write test script");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("failed to run rlox");
+    let _ = fs::remove_file(&path);
+
+    Run {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        status: output.status.code().unwrap_or(-1),
+    }
+}
+
+/// Feeds `lines` to the REPL one at a time, the prompts are left in `stdout`
+pub fn repl(lines: &[&str]) -> Run {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run rlox");
+    let mut stdin = child.stdin.take().unwrap();
+    for line in lines {
+        writeln!(stdin, "{}", line).expect("failed to write to the repl");
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output().expect("failed to run rlox");
+    Run {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        status: output.status.code().unwrap_or(-1),
+    }
+}
+
+/// The exit status and the first line of what went to stderr
+pub fn first_error(source: &str) -> (i32, String) {
+    let run = run(source);
+    (run.status, run.stderr.lines().next().unwrap_or_default().to_owned())
+}
+
+/// Runs `source` and asserts it succeeded, returning the printed lines
+pub fn output(source: &str) -> Vec<String> {
+    let run = run(source);
+    assert_eq!(run.status, 0, "script failed:\n{}", run.stderr);
+    run.stdout.lines().map(str::to_owned).collect()
+}
+
+/// Scans and parses `source` without running it, panicking on any error
+pub fn parse(source: &str) -> Vec<Stmt> {
+    let tokens = Scanner::new(source).map(|token| token.expect("scan error"));
+    Parser::new(tokens).parse().expect("parse error")
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{first_error, output};
+
+#[test]
+fn if_picks_a_branch_by_truthiness() {
+    let lines = output(
+        r#"
+        if (true) print "then"; else print "else";
+        if (false) print "then"; else print "else";
+        if (nil) print "nil is truthy";
+        if (0) print "0 is truthy";
+        if ("") print "empty string is truthy";
+        "#,
+    );
+    assert_eq!(lines, ["then", "else", "0 is truthy", "empty string is truthy"]);
+}
+
+#[test]
+fn else_binds_to_the_nearest_if() {
+    assert_eq!(output("if (true) if (false) print 1; else print 2;"), ["2"]);
+    assert_eq!(output("if (false) if (true) print 1; else print 2;"), Vec::<String>::new());
+}
+
+#[test]
+fn while_loops_until_the_condition_is_falsey() {
+    let lines = output("var i = 0;\nwhile (i < 3) {\n  print i;\n  i = i + 1;\n}\nwhile (false) print \"never\";");
+    assert_eq!(lines, ["0", "1", "2"]);
+}
+
+#[test]
+fn for_runs_every_clause() {
+    assert_eq!(output("for (var i = 0; i < 3; i = i + 1) print i;"), ["0", "1", "2"]);
+}
+
+#[test]
+fn each_for_clause_can_be_left_out() {
+    let lines = output(
+        r#"
+        var i = 0;
+        for (; i < 2; i = i + 1) print "no initializer";
+
+        for (var j = 0; j < 2;) {
+            print "no increment";
+            j = j + 1;
+        }
+
+        fun first() {
+            for (var k = 0;; k = k + 1) {
+                if (k == 1) return "no condition";
+            }
+        }
+        print first();
+
+        fun none() {
+            for (;;) return "no clauses";
+        }
+        print none();
+        "#,
+    );
+    assert_eq!(
+        lines,
+        ["no initializer", "no initializer", "no increment", "no increment", "no condition", "no clauses"]
+    );
+}
+
+#[test]
+fn for_can_start_from_an_existing_variable() {
+    assert_eq!(output("var i;\nfor (i = 5; i < 7; i = i + 1) {}\nprint i;"), ["7"]);
+}
+
+#[test]
+fn the_for_loop_variable_does_not_leak() {
+    let (status, error) = first_error("for (var i = 0; i < 1; i = i + 1) {}\nprint i;");
+    assert_eq!(status, 70);
+    assert_eq!(error, "error[E0302]: Undefined variable 'i'");
+
+    // nor does it clobber an outer one of the same name
+    assert_eq!(output("var i = \"outer\";\nfor (var i = 0; i < 2; i = i + 1) {}\nprint i;"), ["outer"]);
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    let lines = output(
+        r#"
+        fun loud(value) { print "evaluated"; return value; }
+        print false and loud(1);
+        print true or loud(2);
+        print nil or "fallback";
+        print 1 and 2;
+        "#,
+    );
+    assert_eq!(lines, ["false", "true", "fallback", "2"]);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{repl, run, run_with_args};
+
+#[test]
+fn human_output_shows_code_location_and_underline() {
+    let run = run("var a = 1;\nprint a + \"b\";\n");
+    assert_eq!(run.status, 70);
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert_eq!(lines[0], "error[E0301]: Operands must be two numbers or two strings");
+    assert!(lines[1].ends_with(":2:9"), "location line was `{}`", lines[1]);
+    assert_eq!(&lines[2..], ["  |", "2 | print a + \"b\";", "  |         ^"]);
+}
+
+#[test]
+fn duplicate_declaration_points_at_the_first_one() {
+    let run = run("{\n  var a = 1;\n  var a = 2;\n}\n");
+    assert_eq!(run.status, 65);
+    assert!(run.stderr.starts_with("error[E0201]: "), "stderr was:\n{}", run.stderr);
+    assert!(run.stderr.contains("  |       - first declared here\n"), "stderr was:\n{}", run.stderr);
+}
+
+#[test]
+fn json_output_is_one_object_per_line() {
+    let run = run_with_args(&["--error-format=json"], "var x = 1 @;\nprint (x;\n");
+    assert_eq!(run.status, 65);
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert_eq!(lines.len(), 2, "stderr was:\n{}", run.stderr);
+    assert!(lines[0].starts_with(r#"{"severity":"error","code":"E0001","message":"Unexpected character '@'","#));
+    assert!(lines[0].contains(r#""labels":[{"primary":true,"message":"","start":10,"end":11,"line":1,"column":11}]"#));
+    assert!(lines[1].starts_with(r#"{"severity":"error","code":"E0101","message":"Expect ')' after expression","#));
+}
+
+#[test]
+fn repl_errors_are_shown_against_the_line_that_defined_the_code() {
+    let run = repl(&["fun f() { return nil + 1; }", "print \"aéééééééééé\"; f();"]);
+    assert!(run.stdout.contains("aéééééééééé"), "stdout was:\n{}", run.stdout);
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert_eq!(lines[0], "error[E0301]: Operands must be two numbers or two strings");
+    assert_eq!(lines[1], " --> <repl>:1:22");
+    assert_eq!(&lines[2..], ["  |", "1 | fun f() { return nil + 1; }", "  |                      ^"]);
+}
+
+#[test]
+fn unreadable_script_is_reported() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .arg("/nonexistent/script.lox")
+        .output()
+        .expect("failed to run rlox");
+    assert_eq!(output.status.code(), Some(66));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("Could not read '/nonexistent/script.lox': "), "stderr was:\n{}", stderr);
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{first_error, output};
+
+#[test]
+fn runaway_recursion_is_a_runtime_error() {
+    for source in [
+        "fun f(n) {\n  if (n == 0) return 0;\n  return f(n - 1);\n}\nprint f(5000);",
+        "fun f() {\n  f();\n}\nf();",
+        "class A {\n  init() {\n    A();\n  }\n}\nA();",
+    ] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 70, "running `{}`", source);
+        assert_eq!(error, "error[E0310]: Stack overflow", "running `{}`", source);
+    }
+}
+
+#[test]
+fn deep_recursion_below_the_limit_works() {
+    let lines = output("fun f(n) {\n  if (n == 0) return 0;\n  return 1 + f(n - 1);\n}\nprint f(900);\nprint f(900);");
+    assert_eq!(lines, ["900", "900"]);
+}
+
+#[test]
+fn calling_with_the_wrong_number_of_arguments_is_an_error() {
+    for (source, message) in [
+        ("fun f(a, b) {}\nf(1);", "error[E0305]: Expected 2 arguments but got 1"),
+        ("fun f() {}\nf(1, 2);", "error[E0305]: Expected 0 arguments but got 2"),
+    ] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 70, "running `{}`", source);
+        assert_eq!(error, message, "running `{}`", source);
+    }
+}
+
+#[test]
+fn only_functions_and_classes_can_be_called() {
+    let (status, error) = first_error("var a = \"not a function\";\na();");
+    assert_eq!(status, 70);
+    assert_eq!(error, "error[E0304]: Can only call functions and classes");
+}
+
+#[test]
+fn parameters_and_arguments_are_limited_to_255() {
+    let names = |count: usize| (0..count).map(|i| format!("a{}", i)).collect::<Vec<_>>().join(", ");
+    let numbers = |count: usize| (0..count).map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+
+    // exactly at the limit is fine
+    let source = format!("fun f({}) {{ return a254; }}\nprint f({});", names(255), numbers(255));
+    assert_eq!(output(&source), ["254"]);
+
+    for (source, message) in [
+        (format!("fun f({}) {{}}", names(256)), "error[E0104]: Can't have more than 255 parameters"),
+        (format!("fun f() {{}}\nf({});", numbers(256)), "error[E0104]: Can't have more than 255 arguments"),
+    ] {
+        let (status, error) = first_error(&source);
+        assert_eq!(status, 65);
+        assert_eq!(error, message);
+    }
+}
+
+#[test]
+fn return_unwinds_out_of_nested_loops_and_blocks() {
+    let lines = output(
+        r#"
+        fun find(target) {
+            for (var i = 1; i < 10; i = i + 1) {
+                var j = 1;
+                while (j <= i) {
+                    {
+                        if (i * j == target) return "${i} * ${j}";
+                    }
+                    j = j + 1;
+                }
+            }
+            return "none";
+        }
+
+        fun forever() {
+            while (true) {
+                for (;;) {
+                    return "out";
+                }
+            }
+        }
+
+        print find(6);
+        print find(12);
+        print find(11);
+        print forever();
+        "#,
+    );
+    assert_eq!(lines, ["3 * 2", "4 * 3", "none", "out"]);
+}
+
+#[test]
+fn bare_return_and_falling_off_the_end_give_nil() {
+    let lines = output(
+        r#"
+        fun early(flag) {
+            if (flag) return;
+            print "not returned";
+        }
+        fun nothing() {}
+        print early(true);
+        print early(false);
+        print nothing();
+        "#,
+    );
+    assert_eq!(lines, ["nil", "not returned", "nil", "nil"]);
+}
+
+#[test]
+fn functions_print_their_name() {
+    let lines = output("fun greet() {}\nprint greet;\nvar alias = greet;\nprint alias;");
+    assert_eq!(lines, ["<fn greet>", "<fn greet>"]);
+}
+
+#[test]
+fn functions_are_first_class() {
+    let lines = output(
+        r#"
+        fun twice(f, x) { return f(f(x)); }
+        fun inc(n) { return n + 1; }
+        print twice(inc, 1);
+        "#,
+    );
+    assert_eq!(lines, ["3"]);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn identifiers_may_contain_digits_after_the_first_character() {
+    let lines = output("var x1 = 1;\nvar _a2_b3 = x1 + 1;\nprint x1;\nprint _a2_b3;\n");
+    assert_eq!(lines, ["1", "2"]);
+}
+
+#[test]
+fn identifiers_follow_xid_rules() {
+    let lines = output("var αβγ = 1;\nvar naïve = 2;\nvar 変数 = 3;\nprint αβγ + naïve + 変数;\n");
+    assert_eq!(lines, ["6"]);
+}
+
+#[test]
+fn identifiers_are_nfc_normalised() {
+    // declared precomposed (U+00E9), used decomposed (e + U+0301)
+    let lines = output("var caf\u{e9} = \"same\";\nprint cafe\u{301};\n");
+    assert_eq!(lines, ["same"]);
+}
+
+#[test]
+fn a_digit_cannot_start_an_identifier() {
+    let run = run("var 1x = 1;");
+    assert_eq!(run.status, 65);
+    assert_eq!(run.stderr.lines().next().unwrap_or_default(), "error[E0006]: Invalid number literal '1x'");
+}
+
+#[test]
+fn columns_count_grapheme_clusters() {
+    // the decomposed `é` and the joined family emoji are one column each
+    let run = run("print \"e\u{301}\u{1F468}\u{200D}\u{1F469}\" + nil;");
+    assert_eq!(run.status, 70);
+    let location = run.stderr.lines().nth(1).unwrap_or_default();
+    assert!(location.ends_with(":1:12"), "location line was `{}`", location);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use common::{first_error, output};
+
+#[test]
+fn methods_are_found_through_the_superclass_chain() {
+    let lines = output(
+        r#"
+        class A {
+            method() { print "A method"; }
+            other() { print "A other"; }
+        }
+        class B < A {
+            method() { print "B method"; }
+        }
+        class C < B {}
+
+        C().method();
+        C().other();
+        "#,
+    );
+    assert_eq!(lines, ["B method", "A other"]);
+}
+
+#[test]
+fn init_is_inherited() {
+    let lines = output(
+        r#"
+        class Base {
+            init(value) { this.value = value; }
+        }
+        class Derived < Base {}
+        print Derived(7).value;
+        "#,
+    );
+    assert_eq!(lines, ["7"]);
+}
+
+#[test]
+fn super_calls_the_superclass_method_bound_to_this() {
+    let lines = output(
+        r#"
+        class A {
+            method() { print "A " + this.name; }
+        }
+        class B < A {
+            method() {
+                print "B " + this.name;
+                super.method();
+            }
+            test() {
+                var bound = super.method;
+                return bound;
+            }
+        }
+        class C < B {}
+
+        var c = C();
+        c.name = "c";
+        c.method();
+        c.test()();
+        "#,
+    );
+    // `super` is resolved from the class the method is written in, not from `this`
+    assert_eq!(lines, ["B c", "A c", "A c"]);
+}
+
+#[test]
+fn super_inside_a_closure_keeps_its_class() {
+    let lines = output(
+        r#"
+        class A {
+            say() { print "A"; }
+        }
+        class B < A {
+            getClosure() {
+                fun closure() { super.say(); }
+                return closure;
+            }
+            say() { print "B"; }
+        }
+        B().getClosure()();
+        "#,
+    );
+    assert_eq!(lines, ["A"]);
+}
+
+#[test]
+fn superclass_must_be_a_class() {
+    for source in ["var NotAClass = \"nope\";\nclass A < NotAClass {}", "fun f() {}\nclass A < f {}"] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 70, "running `{}`", source);
+        assert_eq!(error, "error[E0307]: Superclass must be a class", "running `{}`", source);
+    }
+}
+
+#[test]
+fn a_class_cannot_inherit_from_itself() {
+    let (status, error) = first_error("class A < A {}");
+    assert_eq!(status, 65);
+    assert_eq!(error, "error[E0206]: A class can't inherit from itself");
+}
+
+#[test]
+fn super_needs_a_subclass() {
+    for (source, message) in [
+        ("print super.method;", "error[E0207]: Can't use 'super' outside of a class"),
+        ("fun f() {\n  super.method();\n}", "error[E0207]: Can't use 'super' outside of a class"),
+        ("class A {\n  m() {\n    super.m();\n  }\n}", "error[E0208]: Can't use 'super' in a class with no superclass"),
+    ] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 65, "running `{}`", source);
+        assert_eq!(error, message, "running `{}`", source);
+    }
+}
+
+#[test]
+fn missing_super_method_is_a_runtime_error() {
+    let (status, error) = first_error("class A {}\nclass B < A {\n  m() {\n    super.missing();\n  }\n}\nB().m();");
+    assert_eq!(status, 70);
+    assert_eq!(error, "error[E0303]: Undefined property 'missing'");
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn numbers_are_double_precision() {
+    let lines = output("print 16777217;\nprint 16777217 + 1;\nprint 0.1 + 0.2;\n");
+    assert_eq!(lines, ["16777217", "16777218", "0.30000000000000004"]);
+}
+
+#[test]
+fn numeric_literal_forms() {
+    for (literal, expected) in [
+        ("0xFF", "255"),
+        ("0Xff", "255"),
+        ("0b1010", "10"),
+        ("0B1", "1"),
+        ("1_000_000", "1000000"),
+        ("0xFF_FF", "65535"),
+        ("0b1111_0000", "240"),
+        ("1_0.2_5", "10.25"),
+        ("1e3", "1000"),
+        ("2.5E-3", "0.0025"),
+        ("1e+2", "100"),
+        ("1_0e1_0", "100000000000"),
+    ] {
+        assert_eq!(output(&format!("print {};", literal)), [expected], "printing `{}`", literal);
+    }
+}
+
+#[test]
+fn malformed_literals_are_scan_errors() {
+    for (literal, message) in [
+        ("1__0", "Invalid number literal '1__0'"),
+        ("1_", "Invalid number literal '1_'"),
+        ("0x", "Invalid number literal '0x'"),
+        ("0x_1", "Invalid number literal '0x_1'"),
+        ("0b102", "Invalid number literal '0b102'"),
+        ("12abc", "Invalid number literal '12abc'"),
+        ("1e", "Invalid number literal '1e'"),
+        ("1e400", "Number literal '1e400' is out of range"),
+        ("0x1_0000_0000_0000_0000", "Number literal '0x1_0000_0000_0000_0000' is out of range"),
+        ("0x8000_0000_0000_0000", "Number literal '0x8000_0000_0000_0000' is out of range"),
+        ("9223372036854775808", "Number literal '9223372036854775808' is out of range"),
+    ] {
+        let run = run(&format!("print {};", literal));
+        assert_eq!(run.status, 65, "scanning `{}`", literal);
+        let errors: Vec<&str> = run.stderr.lines().filter(|line| line.starts_with("error")).collect();
+        assert_eq!(errors, [format!("error[E0006]: {}", message)], "scanning `{}`", literal);
+    }
+}
+
+#[test]
+fn integers_are_exact_beyond_two_to_the_fifty_three() {
+    let lines = output(
+        r#"
+        var big = 9007199254740993;
+        print big;
+        print big + 2;
+        print 9223372036854775807;
+        print 0x7FFF_FFFF_FFFF_FFFF - 1;
+        "#,
+    );
+    assert_eq!(lines, ["9007199254740993", "9007199254740995", "9223372036854775807", "9223372036854775806"]);
+}
+
+#[test]
+fn mixed_comparisons_are_exact() {
+    for (expr, expected) in [
+        ("9007199254740993 == 9007199254740992.0", "false"),
+        ("9007199254740992.0 == 9007199254740993", "false"),
+        ("9007199254740992 == 9007199254740992.0", "true"),
+        ("9007199254740993 > 9007199254740992.0", "true"),
+        ("9007199254740992.0 < 9007199254740993", "true"),
+        ("9007199254740993 <= 9007199254740992.0", "false"),
+        ("9223372036854775807 < 9223372036854775808.0", "true"),
+        ("9223372036854775807 == 9223372036854775808.0", "false"),
+        ("-9223372036854775807 - 1 == -9223372036854775808.0", "true"),
+        ("1 == 1.5", "false"),
+        ("1 < 2.0", "true"),
+        ("1 < 0.0 / 0", "false"),
+        ("1 > 0.0 / 0", "false"),
+        ("1 == 0.0 / 0", "false"),
+    ] {
+        assert_eq!(output(&format!("print {};", expr)), [expected], "evaluating `{}`", expr);
+    }
+}
+
+#[test]
+fn mixed_arithmetic_promotes_to_float() {
+    for (expr, expected) in [
+        ("1 + 0.5", "1.5"),
+        ("3 * 2.5", "7.5"),
+        ("10 / 4", "2.5"),
+        ("4 / 2", "2"),
+        ("7 ~/ 2", "3"),
+        ("-7 ~/ 2", "-3"),
+        ("7.5 ~/ 2", "3"),
+        ("7 % 3", "1"),
+        ("-7 % 3", "-1"),
+        ("7.5 % 2", "1.5"),
+        ("1.0 ~/ 0", "inf"),
+        ("1 == 1.0", "true"),
+        ("2 > 1.5", "true"),
+        ("-2 < -1.5", "true"),
+        ("1 < 1.5", "true"),
+        ("-1 > -1.5", "true"),
+        ("\"${6 * 7} ${0.5}\"", "42 0.5"),
+    ] {
+        assert_eq!(output(&format!("print {};", expr)), [expected], "evaluating `{}`", expr);
+    }
+}
+
+#[test]
+fn integer_overflow_and_division_by_zero_are_runtime_errors() {
+    for (source, message) in [
+        ("print 9223372036854775807 + 1;", "error[E0308]: Integer overflow evaluating '+'"),
+        ("print -9223372036854775807 - 2;", "error[E0308]: Integer overflow evaluating '-'"),
+        ("print 3037000500 * 3037000500;", "error[E0308]: Integer overflow evaluating '*'"),
+        ("print -(-9223372036854775807 - 1);", "error[E0308]: Integer overflow evaluating '-'"),
+        ("print 1 % 0;", "error[E0309]: Division by zero"),
+        ("print 5 ~/ 0;", "error[E0309]: Division by zero"),
+    ] {
+        let run = run(source);
+        assert_eq!(run.status, 70, "running `{}`", source);
+        assert_eq!(run.stderr.lines().next().unwrap_or_default(), message, "running `{}`", source);
+    }
+}
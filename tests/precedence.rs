@@ -0,0 +1,94 @@
+mod common;
+
+use common::{output, run};
+
+/// Each case is a single expression and what `print` shows for it
+const CASES: &[(&str, &str)] = &[
+    // factor binds tighter than term
+    ("2 + 3 * 4", "14"),
+    ("20 - 6 / 2", "17"),
+    ("(2 + 3) * 4", "20"),
+    // term and factor are left associative
+    ("10 - 4 - 3", "3"),
+    ("48 / 4 / 2", "6"),
+    ("2 * 3 / 6", "1"),
+    ("7 % 4 * 2", "6"),
+    ("2 * 7 % 4", "2"),
+    ("17 ~/ 4 ~/ 2", "2"),
+    ("1 + 7 ~/ 2", "4"),
+    ("10 - 7 % 4", "7"),
+    // unary binds tighter than factor and nests to the right
+    ("-2 * 3", "-6"),
+    ("- -3", "3"),
+    ("!!true", "true"),
+    ("-(1 + 2)", "-3"),
+    // every comparison operator
+    ("1 < 2", "true"),
+    ("2 < 2", "false"),
+    ("2 <= 2", "true"),
+    ("3 <= 2", "false"),
+    ("3 > 2", "true"),
+    ("2 > 2", "false"),
+    ("2 >= 2", "true"),
+    ("1 >= 2", "false"),
+    // term binds tighter than comparison
+    ("1 + 2 < 2 + 2", "true"),
+    ("2 * 3 >= 7", "false"),
+    // comparison binds tighter than equality
+    ("1 < 2 == true", "true"),
+    ("2 <= 1 != false", "false"),
+    ("!true == false", "true"),
+    // equality is left associative
+    ("1 == 1 == true", "true"),
+    ("false == false == false", "false"),
+    // equality binds tighter than and, and tighter than or
+    ("1 == 2 and true", "false"),
+    ("true or false and false", "true"),
+    ("false and true or true", "true"),
+    ("nil or false and \"x\"", "false"),
+    ("nil or \"default\"", "default"),
+    ("1 and 2 and 3", "3"),
+];
+
+#[test]
+fn operator_precedence_and_associativity() {
+    let source: String = CASES
+        .iter()
+        .map(|(expr, _)| format!("print {};\n", expr))
+        .collect();
+    let lines = output(&source);
+
+    assert_eq!(lines.len(), CASES.len());
+    for ((expr, expected), actual) in CASES.iter().zip(lines) {
+        assert_eq!(&actual, expected, "evaluating `{}`", expr);
+    }
+}
+
+#[test]
+fn assignment_is_right_associative() {
+    let lines = output(
+        r#"
+        var a;
+        var b;
+        a = b = 1 + 2 * 3;
+        print a;
+        print b;
+        "#,
+    );
+    assert_eq!(lines, ["7", "7"]);
+}
+
+#[test]
+fn comparing_mixed_types_is_a_runtime_error() {
+    for (source, message) in [
+        ("print 1 < \"2\";", "Operands of '<' must be numbers, got number and string"),
+        ("print true >= 1;", "Operands of '>=' must be numbers, got boolean and number"),
+        ("print 1 < 2 < 3;", "Operands of '<' must be numbers, got boolean and number"),
+        ("print nil <= nil;", "Operands of '<=' must be numbers, got nil and nil"),
+    ] {
+        let run = run(source);
+        assert_eq!(run.status, 70, "running `{}`", source);
+        let first_line = run.stderr.lines().next().unwrap_or_default();
+        assert_eq!(first_line, format!("error[E0301]: {}", message));
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::run;
+
+/// The `error[...]` and `-->` lines of every diagnostic, in the order they were printed
+fn reported(source: &str) -> Vec<(String, String)> {
+    let run = run(source);
+    assert_eq!(run.status, 65, "stderr was:\n{}", run.stderr);
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    lines
+        .windows(2)
+        .filter(|pair| pair[0].starts_with("error"))
+        .map(|pair| {
+            let location = pair[1].rsplit(':').take(2).collect::<Vec<_>>();
+            (pair[0].to_owned(), format!("{}:{}", location[1], location[0]))
+        })
+        .collect()
+}
+
+#[test]
+fn every_syntax_error_in_a_file_is_reported() {
+    let source = "var a = ;\nprint 1\nvar b = 2;\nfun f( { }\nprint a + ;\nvar ok = 1;\n";
+    assert_eq!(
+        reported(source),
+        [
+            ("error[E0102]: Expect expression".to_owned(), "1:9".to_owned()),
+            ("error[E0101]: Expect ';' after value".to_owned(), "3:1".to_owned()),
+            ("error[E0101]: Expect parameter name".to_owned(), "4:8".to_owned()),
+            ("error[E0102]: Expect expression".to_owned(), "5:11".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn parsing_recovers_inside_blocks() {
+    let source = "{\n  var c = );\n  print c;\n  print (;\n  var d = 1;\n}\nprint 2 +;\n";
+    assert_eq!(
+        reported(source),
+        [
+            ("error[E0102]: Expect expression".to_owned(), "2:11".to_owned()),
+            ("error[E0102]: Expect expression".to_owned(), "4:10".to_owned()),
+            ("error[E0102]: Expect expression".to_owned(), "7:10".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn nothing_runs_when_there_are_syntax_errors() {
+    let run = run("print \"before\";\nprint (;\nprint \"after\";\n");
+    assert_eq!(run.status, 65);
+    assert_eq!(run.stdout, "");
+}
+
+#[test]
+fn scan_and_parse_errors_come_out_in_source_order() {
+    let source = "var a = ;\nprint 1\nvar b = 2;\nprint (;\nvar c = @ 1;\n";
+    assert_eq!(
+        reported(source),
+        [
+            ("error[E0102]: Expect expression".to_owned(), "1:9".to_owned()),
+            ("error[E0101]: Expect ';' after value".to_owned(), "3:1".to_owned()),
+            ("error[E0102]: Expect expression".to_owned(), "4:8".to_owned()),
+            ("error[E0001]: Unexpected character '@'".to_owned(), "5:9".to_owned()),
+        ]
+    );
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{first_error, output, run};
+
+#[test]
+fn closures_keep_the_variable_they_captured_when_it_is_shadowed() {
+    let lines = output(
+        r#"
+        var a = "global";
+        {
+            fun showA() {
+                print a;
+            }
+
+            showA();
+            var a = "block";
+            showA();
+            print a;
+        }
+        "#,
+    );
+    assert_eq!(lines, ["global", "global", "block"]);
+}
+
+#[test]
+fn reading_a_local_in_its_own_initializer_is_an_error() {
+    let (status, error) = first_error("var a = 1;\n{\n  var a = a;\n}\n");
+    assert_eq!(status, 65);
+    assert_eq!(error, "error[E0202]: Can't read local variable in its own initializer");
+
+    // globals are late bound, so this is only a runtime lookup
+    assert_eq!(output("var a = 1;\nvar a = a + 1;\nprint a;"), ["2"]);
+}
+
+#[test]
+fn duplicate_local_declarations_are_errors() {
+    for source in ["{\n  var a = 1;\n  var a = 2;\n}", "fun f(a, a) {}", "fun f(a) {\n  var a;\n}"] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 65, "running `{}`", source);
+        assert_eq!(error, "error[E0201]: Already a variable with this name in this scope", "running `{}`", source);
+    }
+
+    // redeclaring a global or shadowing in an inner scope is fine
+    assert_eq!(output("var a = 1;\nvar a = 2;\n{\n  var a = 3;\n  print a;\n}\nprint a;"), ["3", "2"]);
+}
+
+#[test]
+fn returning_from_top_level_code_is_an_error() {
+    let (status, error) = first_error("print 1;\nreturn 2;\n");
+    assert_eq!(status, 65);
+    assert_eq!(error, "error[E0203]: Can't return from top-level code");
+}
+
+#[test]
+fn resolver_errors_stop_the_program_before_it_runs() {
+    let run = run("print \"before\";\n{\n  var a = a;\n}\nreturn;\n");
+    assert_eq!(run.status, 65);
+    assert_eq!(run.stdout, "");
+    let errors = run.stderr.lines().filter(|line| line.starts_with("error")).count();
+    assert_eq!(errors, 2, "stderr was:\n{}", run.stderr);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::parse;
+use rlox::types::expr::Expr;
+use rlox::types::operations::Spanned;
+use rlox::types::stmt::Stmt;
+
+/// The source text each top level statement's span covers
+fn spanned_text(source: &str) -> Vec<&str> {
+    parse(source)
+        .iter()
+        .map(|stmt| {
+            let span = stmt.spanned().expect("statement has no span");
+            &source[span.start..span.end]
+        })
+        .collect()
+}
+
+#[test]
+fn literals_have_the_span_of_their_token() {
+    let source = "1 + 2.5;\nprint \"x\";\nnil;";
+    assert_eq!(spanned_text(source), ["1 + 2.5", "print \"x\"", "nil"]);
+
+    let statements = parse(source);
+    let Stmt::Expression { expression } = &statements[0] else {
+        panic!("expected an expression statement");
+    };
+    let Expr::Binary { left, right, .. } = &**expression else {
+        panic!("expected a binary expression");
+    };
+    let left = left.spanned().unwrap();
+    let right = right.spanned().unwrap();
+    assert_eq!((left.start, left.end, left.line, left.column), (0, 1, 1, 1));
+    assert_eq!((right.start, right.end, right.line, right.column), (4, 7, 1, 5));
+}
+
+#[test]
+fn spans_run_from_the_first_token_to_the_last_kept_by_the_node() {
+    let source = "var x = 1;\nx = -x * (2);\nobj.field.method(true, \"é\");\nvar s = \"a ${x} b\";";
+    assert_eq!(
+        spanned_text(source),
+        ["var x = 1", "x = -x * (2)", "obj.field.method(true, \"é\")", "var s = \"a ${x} b\""]
+    );
+}
+
+#[test]
+fn statement_spans_cover_their_bodies() {
+    let source = "if (a) print 1; else print 2;\nwhile (true) { print nil; }\nfun f(a) { return a; }\nfor (;;) print 3;";
+    let spans = spanned_text(source);
+    assert_eq!(spans[0], "if (a) print 1; else print 2");
+    assert_eq!(spans[1], "while (true) { print nil; }");
+    assert_eq!(spans[2], "f(a) { return a; }");
+    assert_eq!(spans[3], "for (;;) print 3");
+}
+
+#[test]
+fn blocks_and_classes_span_their_braces() {
+    let source = "{ print 1; }\nclass A < B { m() {} }\n{}\nfor (var i = 0; i < 1; i = i + 1) { print i; }";
+    let spans = spanned_text(source);
+    assert_eq!(spans[0], "{ print 1; }");
+    assert_eq!(spans[1], "class A < B { m() {} }");
+    assert_eq!(spans[2], "{}");
+    assert_eq!(spans[3], "for (var i = 0; i < 1; i = i + 1) { print i; }");
+}
+
+#[test]
+fn spans_on_later_lines_know_their_line_and_column() {
+    let statements = parse("var a = 1;\n  print a;");
+    let span = statements[1].spanned().unwrap();
+    assert_eq!((span.line, span.column), (2, 3));
+}
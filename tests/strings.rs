@@ -0,0 +1,101 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn escapes_are_decoded() {
+    let lines = output(r#"print "tab\there, quote \"q\", backslash \\, nul \0!";"#);
+    assert_eq!(lines, ["tab\there, quote \"q\", backslash \\, nul \0!"]);
+
+    let run = run(r#"print "one\ntwo\r";"#);
+    assert_eq!(run.stdout, "one\ntwo\r\n");
+}
+
+#[test]
+fn unicode_escapes_name_code_points() {
+    let lines = output(r#"print "\u{48}\u{069}\u{1F600} \u{e9}" == "Hi😀 é";"#);
+    assert_eq!(lines, ["true"]);
+}
+
+#[test]
+fn escaped_quote_does_not_end_the_string() {
+    let lines = output(r#"print "say \"hi\"" + "!";"#);
+    assert_eq!(lines, ["say \"hi\"!"]);
+}
+
+#[test]
+fn bad_escapes_are_scan_errors() {
+    for (source, code, message, column) in [
+        (r#"print "\q";"#, "E0004", r"Invalid escape sequence '\q'", 8),
+        (r#"print "ok \u{D800}";"#, "E0005", "Invalid code point U+D800 in unicode escape", 11),
+        (r#"print "\u{110000}";"#, "E0005", "Invalid code point U+110000 in unicode escape", 8),
+        (r#"print "\u00e9";"#, "E0004", r"Malformed unicode escape, expected 1 to 6 hex digits between '\u{' and '}'", 8),
+        (r#"print "\u{}";"#, "E0004", r"Malformed unicode escape, expected 1 to 6 hex digits between '\u{' and '}'", 8),
+    ] {
+        let run = run(source);
+        assert_eq!(run.status, 65, "running `{}`", source);
+        let lines: Vec<&str> = run.stderr.lines().collect();
+        assert_eq!(lines[0], format!("error[{}]: {}", code, message), "running `{}`", source);
+        assert!(lines[1].ends_with(&format!(":1:{}", column)), "running `{}`: {}", source, lines[1]);
+    }
+}
+
+#[test]
+fn every_bad_escape_in_a_string_is_reported() {
+    let run = run(r#"print "\a \b \c";"#);
+    assert_eq!(run.status, 65);
+    assert_eq!(run.stderr.matches("error[E0004]").count(), 3, "stderr was:\n{}", run.stderr);
+}
+
+#[test]
+fn interpolation_stringifies_embedded_expressions() {
+    let lines = output(
+        r#"
+        var name = "Ann";
+        var count = 2;
+        fun greet() { return "hi"; }
+        print "Hello ${name}, you have ${count + 1} items";
+        print "${greet()}${nil}${true} ${1 < 2}";
+        print "no interpolation \${here}";
+        "#,
+    );
+    assert_eq!(lines, ["Hello Ann, you have 3 items", "hiniltrue true", "no interpolation ${here}"]);
+}
+
+#[test]
+fn interpolation_allows_nested_strings() {
+    let lines = output(r#"var x = "x"; print "a ${"b ${x + "}"} c"} d";"#);
+    assert_eq!(lines, ["a b x} c d"]);
+}
+
+#[test]
+fn interpolation_keeps_line_numbers() {
+    let run = run("print \"first\n${\n  nil + 1\n} last\";\n");
+    assert_eq!(run.status, 70);
+    assert!(run.stderr.contains("\n3 |   nil + 1\n"), "stderr was:\n{}", run.stderr);
+}
+
+#[test]
+fn malformed_interpolation_is_a_syntax_error() {
+    for (source, message) in [
+        (r#"print "${}";"#, "error[E0102]: Expect expression"),
+        (r#"print "${1 +}";"#, "error[E0102]: Expect expression"),
+        (r#"print "${1 2}";"#, "error[E0101]: Expect '}' after interpolated expression"),
+        (r#"print "${1";"#, "error[E0002]: Unterminated string"),
+    ] {
+        let run = run(source);
+        assert_eq!(run.status, 65, "running `{}`", source);
+        let first_line = run.stderr.lines().next().unwrap_or_default();
+        assert_eq!(first_line, message, "running `{}`", source);
+    }
+}
+
+#[test]
+fn unterminated_string_is_reported_once() {
+    for source in ["print \"abc", "var x = \"abc;\nprint x;", "print \"a ${1} b", "print \"${1"] {
+        let run = run(source);
+        assert_eq!(run.status, 65, "running `{}`", source);
+        let errors: Vec<&str> = run.stderr.lines().filter(|line| line.starts_with("error")).collect();
+        assert_eq!(errors, ["error[E0002]: Unterminated string"], "running `{}`", source);
+    }
+}
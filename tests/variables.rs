@@ -0,0 +1,80 @@
+mod common;
+
+use common::{first_error, output, run};
+
+#[test]
+fn undefined_variables_are_runtime_errors() {
+    for source in ["print x;", "x = 1;", "{\n  print x;\n}"] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 70, "running `{}`", source);
+        assert_eq!(error, "error[E0302]: Undefined variable 'x'", "running `{}`", source);
+    }
+}
+
+#[test]
+fn code_before_an_undefined_variable_still_runs() {
+    let run = run("print 1;\nprint x;\nprint 2;");
+    assert_eq!(run.status, 70);
+    assert_eq!(run.stdout, "1\n");
+}
+
+#[test]
+fn blocks_shadow_and_then_restore_outer_variables() {
+    let lines = output(
+        r#"
+        var a = "outer";
+        {
+            var a = "inner";
+            print a;
+            {
+                var a = "innermost";
+                print a;
+            }
+            print a;
+        }
+        print a;
+        "#,
+    );
+    assert_eq!(lines, ["inner", "innermost", "inner", "outer"]);
+}
+
+#[test]
+fn assignment_reaches_enclosing_scopes() {
+    let lines = output(
+        r#"
+        var a = 1;
+        var b = 1;
+        {
+            var b = 2;
+            {
+                a = 10;
+                b = 20;
+            }
+            print b;
+        }
+        print a;
+        print b;
+        "#,
+    );
+    assert_eq!(lines, ["20", "10", "1"]);
+}
+
+#[test]
+fn assignment_is_an_expression_and_right_associative() {
+    let lines = output("var a;\nvar b;\na = b = 3;\nprint a;\nprint b;\nprint a = 4;");
+    assert_eq!(lines, ["3", "3", "4"]);
+}
+
+#[test]
+fn uninitialized_variables_are_nil() {
+    assert_eq!(output("var a;\nprint a;"), ["nil"]);
+}
+
+#[test]
+fn invalid_assignment_targets_are_syntax_errors() {
+    for source in ["var a = 1;\na + 2 = 3;", "var a;\n(a) = 1;", "1 = 2;"] {
+        let (status, error) = first_error(source);
+        assert_eq!(status, 65, "running `{}`", source);
+        assert_eq!(error, "error[E0103]: Invalid assignment target", "running `{}`", source);
+    }
+}
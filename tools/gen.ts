@@ -9,7 +9,12 @@ type EnumConfig = {
 interface EnumsConfig {
     [ enumName: string]: EnumConfig
 }
-type OperationsConfig = { [operation: string]: string };
+type OperationConfig = {
+    // extra leading arguments threaded through every call, e.g. interpreter state
+    args: string[];
+    returns: string;
+}
+type OperationsConfig = { [operation: string]: OperationConfig };
 
 interface Config {
     imports: string[];
@@ -19,32 +24,46 @@ interface Config {
 }
 
 const config: Config = {
-    imports: ['use crate::token_type::{Literal};', 'use crate::types::expr::Expr;', 'use crate::types::stmt::Stmt;'],
+    imports: ['use crate::types::expr::Expr;', 'use crate::types::stmt::Stmt;', 'use crate::types::value::Value;', 'use crate::interpreter::Interpreter;', 'use crate::resolver::Resolver;', 'use crate::span::Span;', 'pub use interpret::{RuntimeError, Unwind};'],
     enums: {
         Expr: {
-            imports: ['use crate::token_type::{Token,Literal};'],
+            imports: ['use std::cell::Cell;', '', 'use crate::token_type::{Token,Literal};'],
             variants: {
-                Assign   : ['name: Token', 'value: Box<Expr>'],
+                Assign   : ['name: Token', 'value: Box<Expr>', 'depth: Cell<Option<usize>>'],
                 Binary   : ['left: Box<Expr>', 'operator: Token', 'right: Box<Expr>'],
-                Grouping : ['expression: Box<Expr>'],
-                Literal  : ['value: Literal'],
+                Call     : ['callee: Box<Expr>', 'paren: Token', 'arguments: Vec<Expr>'],
+                Get      : ['object: Box<Expr>', 'name: Token'],
+                Grouping : ['left_paren: Token', 'expression: Box<Expr>', 'right_paren: Token'],
+                Interpolation : ['start: Token', 'parts: Vec<Expr>', 'end: Token'],
+                Literal  : ['value: Literal', 'token: Token'],
+                Logical  : ['left: Box<Expr>', 'operator: Token', 'right: Box<Expr>'],
+                Set      : ['object: Box<Expr>', 'name: Token', 'value: Box<Expr>'],
+                Super    : ['keyword: Token', 'method: Token', 'depth: Cell<Option<usize>>'],
+                This     : ['keyword: Token', 'depth: Cell<Option<usize>>'],
                 Unary    : ['operator: Token', 'right: Box<Expr>'],
-                Variable : ['name: Token']
+                Variable : ['name: Token', 'depth: Cell<Option<usize>>']
             }
         },
         Stmt: {
-            imports: ['use crate::token_type::{Token,Literal};', 'use crate::types::expr::Expr;'],
+            imports: ['use std::rc::Rc;', '', 'use crate::token_type::{Token};', 'use crate::types::expr::Expr;'],
             variants: {
-                Block      : ['statements: Vec<Stmt>'],
+                Block      : ['left_brace: Token', 'statements: Vec<Stmt>', 'right_brace: Token'],
+                Class      : ['keyword: Token', 'name: Token', 'superclass: Option<Box<Expr>>', 'methods: Vec<Stmt>', 'right_brace: Token', 'doc: Option<Rc<str>>'],
                 Expression : ['expression: Box<Expr>'],
-                Print      : ['expression: Box<Expr>'],
-                Var        : ['name: Token', 'initializer: Box<Expr>'],
+                Function   : ['name: Token', 'params: Rc<Vec<Token>>', 'body: Rc<Vec<Stmt>>', 'right_brace: Token', 'doc: Option<Rc<str>>'],
+                If         : ['keyword: Token', 'condition: Box<Expr>', 'then_branch: Box<Stmt>', 'else_branch: Option<Box<Stmt>>'],
+                Print      : ['keyword: Token', 'expression: Box<Expr>'],
+                Return     : ['keyword: Token', 'value: Option<Box<Expr>>'],
+                Var        : ['keyword: Token', 'name: Token', 'initializer: Option<Box<Expr>>', 'doc: Option<Rc<str>>'],
+                While      : ['keyword: Token', 'condition: Box<Expr>', 'body: Box<Stmt>'],
             }
         }
     },
     operations: {
-        Print: 'String',
-        Interpret: 'Literal'
+        Print: { args: [], returns: 'String' },
+        Interpret: { args: ['interpreter: &mut Interpreter'], returns: 'Result<Value, Unwind>' },
+        Resolve: { args: ['resolver: &mut Resolver'], returns: '()' },
+        Spanned: { args: [], returns: 'Option<Span>' }
     }
 }
 
@@ -75,11 +94,11 @@ function generateOperationImpls(operations: OperationsConfig, enums: EnumsConfig
     let code = imports.join('\n') + '\n';
     code =  code + Object.keys(operations).map(operation => `mod ${operation.toLowerCase()};`).join('\n') + '\n';
     for (const operationName of Object.keys(operations)) {
-        const type = operations[operationName];
-        code = code + generateTraits(operationName, type) + '\n';
+        code = code + generateTraits(operationName, operations[operationName]) + '\n';
     }
     for (const operationName of Object.keys(operations)) {
-        const returnType = operations[operationName];
+        const operation = operations[operationName];
+        const extraArgNames = operation.args.map(arg => arg.split(': ')[0]);
 
         for (const enumName of Object.keys(enums)) {
             const enumConfig = enums[enumName];
@@ -87,17 +106,18 @@ function generateOperationImpls(operations: OperationsConfig, enums: EnumsConfig
             const branches: string[] = [];
             for (const variant of Object.keys(enumConfig.variants)) {
 
-                generateFnStub(operationName, variant.toLowerCase(), enumConfig.variants[variant], returnType, baseDir, imports);
+                generateFnStub(operationName, fnName(variant), enumConfig.variants[variant], operation, baseDir, imports);
 
                 const argsNames = enumConfig.variants[variant].map(arg => arg.split(': ')[0]);
+                const callArgs = [...extraArgNames, ...argsNames];
                 branches.push(
-                    `            ${enumName}::${variant} { ${argsNames.join(', ')} } => ${operationName.toLowerCase()}::${variant.toLowerCase()}(${argsNames.join(', ')}),`
+                    `            ${enumName}::${variant} { ${argsNames.join(', ')} } => ${operationName.toLowerCase()}::${fnName(variant)}(${callArgs.join(', ')}),`
                 );
             }
 
             const operationCode =[
                 `impl ${operationName} for ${enumName} {`,
-                `    fn ${operationName.toLowerCase()}(&self) -> ${returnType} {`,
+                `    fn ${operationName.toLowerCase()}(${['&self', ...operation.args].join(', ')})${returnsClause(operation)} {`,
                 `        match self {`,
                 `${branches.join('\n')}`,
                 `        }`,
@@ -115,24 +135,45 @@ function generateOperationImpls(operations: OperationsConfig, enums: EnumsConfig
     return code;
 }
 
-function generateTraits(name: string, type: string): string {
+// variant names that collide with rust keywords when lowercased
+const RAW_KEYWORDS = ['if', 'while', 'for', 'return', 'fn', 'loop', 'match', 'type', 'impl'];
+const RESERVED_KEYWORDS = ['super', 'self', 'crate'];
+
+function fnName(variant: string): string {
+    const name = variant.toLowerCase();
+    if (RAW_KEYWORDS.includes(name)) {
+        return `r#${name}`;
+    }
+    if (RESERVED_KEYWORDS.includes(name)) {
+        // these cannot be raw identifiers
+        return `${name}_`;
+    }
+    return name;
+}
+
+function returnsClause(operation: OperationConfig): string {
+    return operation.returns === '()' ? '' : ` -> ${operation.returns}`;
+}
+
+function generateTraits(name: string, operation: OperationConfig): string {
     return [
         `pub trait ${name} {`,
-        `    fn ${name.toLowerCase()}(&self) -> ${type};`,
+        `    fn ${name.toLowerCase()}(${['&self', ...operation.args].join(', ')})${returnsClause(operation)};`,
         `}`,
     ].join('\n') + '\n';
 }
 
-function generateFnStub(operation: string, variant: string, args: string[], returnType: string, baseDir: string, imports: string[]) {
-    const filePath = join(baseDir, 'operations', `${operation}.rs`);
+function generateFnStub(operationName: string, variant: string, args: string[], operation: OperationConfig, baseDir: string, imports: string[]) {
+    const filePath = join(baseDir, 'operations', `${operationName.toLowerCase()}.rs`);
     const borrowedArgs = args.map(arg => {
         const [name, type] = arg.split(': ');
         return `${name}: &${type}`;
     })
-    const signature = `pub fn ${variant}(${borrowedArgs.join(', ')}) -> ${returnType}`;
+    const signature = `pub fn ${variant}(${[...operation.args, ...borrowedArgs].join(', ')})${returnsClause(operation)}`;
 
     let code = Deno.readTextFileSync(filePath);
-    if (!code.includes(signature)) {
+    // only the name is matched, hand written stubs rename unused params and rewrap long signatures
+    if (!code.includes(`pub fn ${variant}(`)) {
         code = code + '\n' + [
             `${signature} {`,
             '    todo!()',